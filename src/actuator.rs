@@ -0,0 +1,99 @@
+use std::f32::consts::PI;
+
+use crate::path2d::sample_keyframes;
+
+/// Drives the rest length of a [`Spring2D`](crate::Spring2D) over time,
+/// turning the spring into a muscle.
+///
+/// Time advances by the engine timestep on every update.
+pub enum SpringActuator2D {
+    /// Oscillates around the spring's initial rest length,
+    /// `rest + amplitude * sin(2π * frequency * time + phase)`
+    Sine {
+        amplitude: f32,
+        frequency: f32,
+        phase: f32,
+    },
+    /// Rest length interpolated from `(time, rest_length)` keyframes,
+    /// optionally looping
+    Keyframes(Vec<(f32, f32)>, bool),
+    /// Rest length computed by a callback from the spring's initial rest
+    /// length and the elapsed time
+    Callback(Box<dyn FnMut(f32, f32) -> f32>),
+}
+
+impl SpringActuator2D {
+    pub fn sine(amplitude: f32, frequency: f32, phase: f32) -> Self {
+        SpringActuator2D::Sine {
+            amplitude,
+            frequency,
+            phase,
+        }
+    }
+
+    pub fn keyframes(mut keyframes: Vec<(f32, f32)>, looping: bool) -> Self {
+        keyframes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        SpringActuator2D::Keyframes(keyframes, looping)
+    }
+
+    pub fn callback<F: FnMut(f32, f32) -> f32 + 'static>(f: F) -> Self {
+        SpringActuator2D::Callback(Box::new(f))
+    }
+
+    pub(crate) fn rest_length(&mut self, base_rest_length: f32, time: f32) -> f32 {
+        match self {
+            SpringActuator2D::Sine {
+                amplitude,
+                frequency,
+                phase,
+            } => base_rest_length + *amplitude * (2. * PI * *frequency * time + *phase).sin(),
+            SpringActuator2D::Keyframes(keyframes, looping) => {
+                sample_keyframes(keyframes, *looping, time).unwrap_or(base_rest_length)
+            }
+            SpringActuator2D::Callback(f) => f(base_rest_length, time),
+        }
+        .max(0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Spring2D, Vector2D, VerletObject2D};
+
+    fn muscle(actuator: SpringActuator2D) -> VerletObject2D {
+        let mut obj = VerletObject2D::new("muscle");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let b = obj.create_particle(&Vector2D::new(2., 0.));
+        obj.add_spring(Spring2D::new(&a, &b, 0.5, None).with_actuator(actuator));
+        obj
+    }
+
+    fn rest_length(obj: &VerletObject2D) -> f32 {
+        obj.get_springs()[0].get_rest_length()
+    }
+
+    #[test]
+    fn test_sine_actuator_oscillates_around_rest_length() {
+        let mut obj = muscle(SpringActuator2D::sine(1., 0.25, 0.));
+        obj.step_springs(1.);
+        assert!((rest_length(&obj) - 3.).abs() < 1e-5);
+        obj.step_springs(1.);
+        assert!((rest_length(&obj) - 2.).abs() < 1e-5);
+        obj.step_springs(1.);
+        assert!((rest_length(&obj) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_keyframes_and_callback_actuators() {
+        let mut obj = muscle(SpringActuator2D::keyframes(vec![(2., 5.), (0., 1.)], false));
+        obj.step_springs(1.);
+        assert!((rest_length(&obj) - 3.).abs() < 1e-5);
+        obj.step_springs(2.);
+        assert!((rest_length(&obj) - 5.).abs() < 1e-5);
+
+        let mut obj = muscle(SpringActuator2D::callback(|rest, time| rest - time));
+        obj.step_springs(3.);
+        assert_eq!(rest_length(&obj), 0.);
+    }
+}
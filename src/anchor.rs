@@ -0,0 +1,32 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::Vector2D;
+
+/// A shared, movable point in world space.
+///
+/// Cloning an anchor gives another handle to the same point, so a constraint
+/// can hold one handle while the application keeps another and moves it
+/// around, e.g. to follow a character's hand or the mouse pointer.
+#[derive(Clone)]
+pub struct Anchor2D {
+    position: Rc<Cell<Vector2D>>,
+}
+
+impl Anchor2D {
+    pub fn new(position: &Vector2D) -> Anchor2D {
+        Anchor2D {
+            position: Rc::new(Cell::new(*position)),
+        }
+    }
+
+    #[inline]
+    pub fn set_position(&self, position: &Vector2D) {
+        self.position.set(*position);
+    }
+
+    #[inline]
+    pub fn get_position(&self) -> Vector2D {
+        self.position.get()
+    }
+}
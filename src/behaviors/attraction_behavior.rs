@@ -0,0 +1,139 @@
+use std::cell::Cell;
+
+use super::ParticleBehaviour2D;
+use crate::{Anchor2D, Particle2D, Vector2D};
+
+/// How the pull of an [`AttractionBehavior2D`] weakens from its point to
+/// its radius
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Falloff2D {
+    /// Full strength everywhere inside the radius
+    Constant,
+    /// Fades linearly to zero at the radius
+    Linear,
+    /// Fades with the square of the remaining distance, so the pull is
+    /// concentrated near the point
+    Quadratic,
+    /// Fades along a smoothstep curve, with no sudden change at either end
+    Smooth,
+}
+
+impl Falloff2D {
+    /// Strength factor at `t`, the distance as a fraction of the radius
+    #[inline]
+    fn factor(&self, t: f32) -> f32 {
+        let s = 1. - t;
+        match self {
+            Falloff2D::Constant => 1.,
+            Falloff2D::Linear => s,
+            Falloff2D::Quadratic => s * s,
+            Falloff2D::Smooth => s * s * (3. - 2. * s),
+        }
+    }
+}
+
+/// Pulls particles within a radius towards a point, or pushes them away
+/// with a negative strength.
+///
+/// The point is an [`Anchor2D`], keep a handle to it to move the point at
+/// runtime, e.g. with the mouse pointer.
+pub struct AttractionBehavior2D {
+    anchor: Anchor2D,
+    radius: f32,
+    strength: f32,
+    falloff: Falloff2D,
+    jitter: f32,
+    timestep: f32,
+    seed: Cell<u32>,
+}
+
+impl AttractionBehavior2D {
+    #[inline]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(anchor: &Anchor2D, radius: f32, strength: f32) -> Box<dyn ParticleBehaviour2D> {
+        AttractionBehavior2D::new_with(anchor, radius, strength, Falloff2D::Linear, 0.)
+    }
+
+    /// Attraction with a `falloff` curve, and a random force of up to
+    /// `jitter` added to every particle in range
+    pub fn new_with(
+        anchor: &Anchor2D,
+        radius: f32,
+        strength: f32,
+        falloff: Falloff2D,
+        jitter: f32,
+    ) -> Box<dyn ParticleBehaviour2D> {
+        Box::new(AttractionBehavior2D {
+            anchor: anchor.clone(),
+            radius,
+            strength,
+            falloff,
+            jitter,
+            timestep: 1.0,
+            seed: Cell::new(0x9E37_79B9),
+        })
+    }
+
+    /// Next value of a xorshift generator, in `[-1, 1]`
+    #[inline]
+    fn random(&self) -> f32 {
+        let mut x = self.seed.get();
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.seed.set(x);
+        (x as f32 / u32::MAX as f32) * 2. - 1.
+    }
+}
+
+impl ParticleBehaviour2D for AttractionBehavior2D {
+    fn apply(&self, p: &mut Particle2D) {
+        let delta = self.anchor.get_position() - *p.get_position();
+        let dist = delta.magnitude();
+        if dist >= self.radius || dist == 0. {
+            return;
+        }
+
+        let factor = self.falloff.factor(dist / self.radius);
+        let mut force = delta * (self.strength * factor / dist);
+        if self.jitter > 0. {
+            force += Vector2D::new(self.random(), self.random()) * self.jitter;
+        }
+        p.add_force(&(force * self.timestep));
+    }
+
+    fn configure(&mut self, timestep: f32) {
+        self.timestep = timestep;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attracts_within_radius() {
+        let anchor = Anchor2D::new(&Vector2D::new(0., 0.));
+        let attract = AttractionBehavior2D::new(&anchor, 10., 2.);
+        let repel = AttractionBehavior2D::new_with(&anchor, 10., -2., Falloff2D::Constant, 0.);
+
+        let mut near = Particle2D::new(Default::default(), &Vector2D::new(5., 0.));
+        attract.apply(&mut near);
+        near.update(0.);
+        assert_eq!(*near.get_position(), Vector2D::new(4., 0.));
+
+        repel.apply(&mut near);
+        near.update(0.);
+        assert_eq!(*near.get_position(), Vector2D::new(5., 0.));
+
+        let mut far = Particle2D::new(Default::default(), &Vector2D::new(0., 12.));
+        attract.apply(&mut far);
+        far.update(0.);
+        assert_eq!(*far.get_position(), Vector2D::new(0., 12.));
+
+        anchor.set_position(&Vector2D::new(0., 3.));
+        attract.apply(&mut far);
+        far.update(0.);
+        assert!((far.get_position().y - 11.8).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,142 @@
+use super::InteractionParticle2D;
+use crate::Vector2D;
+
+/// Depth after which particles in the same cell are merged instead of
+/// splitting further, so coincident particles cannot recurse forever
+const MAX_DEPTH: u32 = 24;
+
+struct Node {
+    center: Vector2D,
+    half_size: f32,
+    mass: f32,
+    weighted_position: Vector2D,
+    count: usize,
+    body: Option<usize>,
+    first_child: usize,
+}
+
+impl Node {
+    fn new(center: Vector2D, half_size: f32) -> Self {
+        Node {
+            center,
+            half_size,
+            mass: 0.,
+            weighted_position: Vector2D::zero(),
+            count: 0,
+            body: None,
+            first_child: 0,
+        }
+    }
+
+    #[inline]
+    fn add(&mut self, position: Vector2D, mass: f32) {
+        self.mass += mass;
+        self.weighted_position += position * mass;
+        self.count += 1;
+    }
+}
+
+/// Quadtree summarising the mass of far away particles, so gravity can be
+/// approximated in `O(n log n)`
+pub(crate) struct QuadTree {
+    nodes: Vec<Node>,
+}
+
+impl QuadTree {
+    pub fn new(particles: &[InteractionParticle2D]) -> Self {
+        let mut min = Vector2D::new(f32::MAX, f32::MAX);
+        let mut max = Vector2D::new(f32::MIN, f32::MIN);
+        for p in particles {
+            min.x = min.x.min(p.position.x);
+            min.y = min.y.min(p.position.y);
+            max.x = max.x.max(p.position.x);
+            max.y = max.y.max(p.position.y);
+        }
+        let half_size = ((max.x - min.x).max(max.y - min.y) / 2.).max(1.);
+        let center = (min + max) / 2.;
+
+        let mut tree = QuadTree {
+            nodes: vec![Node::new(center, half_size)],
+        };
+        for (i, p) in particles.iter().enumerate() {
+            tree.insert(particles, i, p);
+        }
+        tree
+    }
+
+    fn insert(&mut self, particles: &[InteractionParticle2D], i: usize, p: &InteractionParticle2D) {
+        let mut node = 0;
+        let mut depth = 0;
+        loop {
+            if self.nodes[node].first_child == 0 {
+                if self.nodes[node].count == 0 {
+                    self.nodes[node].body = Some(i);
+                    self.nodes[node].add(p.position, p.weight);
+                    return;
+                }
+                if depth >= MAX_DEPTH {
+                    self.nodes[node].body = None;
+                    self.nodes[node].add(p.position, p.weight);
+                    return;
+                }
+                self.subdivide(node, particles);
+            }
+            self.nodes[node].add(p.position, p.weight);
+            node = self.child_for(node, &p.position);
+            depth += 1;
+        }
+    }
+
+    /// Split a leaf into four children, moving its particle into one of them
+    fn subdivide(&mut self, node: usize, particles: &[InteractionParticle2D]) {
+        let (center, half_size) = (self.nodes[node].center, self.nodes[node].half_size / 2.);
+        let first_child = self.nodes.len();
+        for (x, y) in [(-1., -1.), (1., -1.), (-1., 1.), (1., 1.)] {
+            let offset = Vector2D::new(x * half_size, y * half_size);
+            self.nodes.push(Node::new(center + offset, half_size));
+        }
+        self.nodes[node].first_child = first_child;
+
+        if let Some(j) = self.nodes[node].body.take() {
+            let child = self.child_for(node, &particles[j].position);
+            self.nodes[child].body = Some(j);
+            self.nodes[child].add(particles[j].position, particles[j].weight);
+        }
+    }
+
+    #[inline]
+    fn child_for(&self, node: usize, position: &Vector2D) -> usize {
+        let n = &self.nodes[node];
+        let right = (position.x >= n.center.x) as usize;
+        let below = (position.y >= n.center.y) as usize;
+        n.first_child + right + 2 * below
+    }
+
+    /// Sum of `force(delta, mass)` over all other particles, or groups of
+    /// them that look smaller than `theta` from particle `i`
+    pub fn accumulate(
+        &self,
+        particles: &[InteractionParticle2D],
+        i: usize,
+        theta: f32,
+        force: impl Fn(Vector2D, f32) -> Vector2D,
+    ) -> Vector2D {
+        let position = particles[i].position;
+        let mut total = Vector2D::zero();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let n = &self.nodes[node];
+            if n.count == 0 || n.body == Some(i) || n.mass == 0. {
+                continue;
+            }
+            let delta = n.weighted_position / n.mass - position;
+            let dist = delta.magnitude();
+            if n.first_child == 0 || 2. * n.half_size < theta * dist {
+                total += force(delta, n.mass);
+            } else {
+                stack.extend(n.first_child..n.first_child + 4);
+            }
+        }
+        total
+    }
+}
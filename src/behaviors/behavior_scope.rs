@@ -0,0 +1,43 @@
+use crate::{ObjectKey, Particle2D};
+
+type ParticlePredicate = dyn Fn(ObjectKey, &Particle2D) -> bool;
+
+/// The particles a behaviour added to
+/// [`VerletPhysics2D`](crate::VerletPhysics2D) acts on
+#[derive(Default)]
+pub enum BehaviorScope2D {
+    /// Every particle of every object
+    #[default]
+    All,
+    /// Every particle of one object
+    Object(ObjectKey),
+    /// Every particle of all objects with the tag
+    Tag(&'static str),
+    /// Particles for which the predicate returns `true`
+    Filter(Box<ParticlePredicate>),
+}
+
+impl BehaviorScope2D {
+    pub fn filter(predicate: impl Fn(ObjectKey, &Particle2D) -> bool + 'static) -> Self {
+        BehaviorScope2D::Filter(Box::new(predicate))
+    }
+
+    /// Whether any particle of the object can be in scope
+    #[inline]
+    pub(crate) fn includes_object(&self, object_id: ObjectKey, tag: &str) -> bool {
+        match self {
+            BehaviorScope2D::All | BehaviorScope2D::Filter(_) => true,
+            BehaviorScope2D::Object(id) => *id == object_id,
+            BehaviorScope2D::Tag(t) => *t == tag,
+        }
+    }
+
+    /// Whether a particle of an object already in scope is in scope
+    #[inline]
+    pub(crate) fn includes_particle(&self, object_id: ObjectKey, p: &Particle2D) -> bool {
+        match self {
+            BehaviorScope2D::Filter(predicate) => predicate(object_id, p),
+            _ => true,
+        }
+    }
+}
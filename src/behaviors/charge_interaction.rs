@@ -0,0 +1,120 @@
+use super::{InteractionParticle2D, ParticleInteraction2D};
+use crate::{SpatialHash2D, Vector2D};
+
+/// Coulomb-like force between particles with a charge, see
+/// [`Particle2D::set_charge`](crate::Particle2D::set_charge).
+///
+/// Charges of the same sign repel and opposite charges attract. With a
+/// `cutoff`, only particles closer than it interact, which lets a spatial
+/// hash skip far pairs.
+pub struct ChargeInteraction2D {
+    strength: f32,
+    softening: f32,
+    cutoff: Option<f32>,
+    timestep: f32,
+}
+
+impl ChargeInteraction2D {
+    #[inline]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        strength: f32,
+        softening: f32,
+        cutoff: Option<f32>,
+    ) -> Box<dyn ParticleInteraction2D> {
+        Box::new(ChargeInteraction2D {
+            strength,
+            softening,
+            cutoff,
+            timestep: 1.0,
+        })
+    }
+
+    /// Force on `a` from `b`
+    #[inline]
+    fn force(&self, a: &InteractionParticle2D, b: &InteractionParticle2D) -> Vector2D {
+        let delta = a.position - b.position;
+        let dist_sq = delta.magnitude_sq() + self.softening * self.softening;
+        if dist_sq == 0. {
+            return Vector2D::zero();
+        }
+        delta * (self.strength * a.charge * b.charge / (dist_sq * dist_sq.sqrt()))
+    }
+}
+
+impl ParticleInteraction2D for ChargeInteraction2D {
+    fn apply(&self, particles: &[InteractionParticle2D], forces: &mut [Vector2D]) {
+        let charged: Vec<usize> = (0..particles.len())
+            .filter(|i| particles[*i].charge != 0.)
+            .collect();
+
+        match self.cutoff {
+            // nothing is closer than a non-positive cutoff
+            Some(cutoff) if cutoff <= 0. => {}
+            Some(cutoff) => {
+                let mut grid = SpatialHash2D::new(cutoff);
+                for &i in charged.iter() {
+                    grid.insert(i, &particles[i].position, 0.);
+                }
+                for &i in charged.iter() {
+                    for j in grid.query_radius(&particles[i].position, cutoff) {
+                        if i < j {
+                            let f = self.force(&particles[i], &particles[j]) * self.timestep;
+                            forces[i] += f;
+                            forces[j] -= f;
+                        }
+                    }
+                }
+            }
+            None => {
+                for (n, &i) in charged.iter().enumerate() {
+                    for &j in charged[n + 1..].iter() {
+                        let f = self.force(&particles[i], &particles[j]) * self.timestep;
+                        forces[i] += f;
+                        forces[j] -= f;
+                    }
+                }
+            }
+        }
+    }
+
+    fn configure(&mut self, timestep: f32) {
+        self.timestep = timestep;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn charged(x: f32, charge: f32) -> InteractionParticle2D {
+        InteractionParticle2D {
+            object: Default::default(),
+            particle: Default::default(),
+            position: Vector2D::new(x, 0.),
+            weight: 1.,
+            charge,
+        }
+    }
+
+    #[test]
+    fn test_like_charges_repel_within_cutoff() {
+        let particles = [
+            charged(0., 1.),
+            charged(1., 1.),
+            charged(2., -1.),
+            charged(9., 1.),
+        ];
+        let mut all = [Vector2D::zero(); 4];
+        let mut near = [Vector2D::zero(); 4];
+        ChargeInteraction2D::new(1., 0., None).apply(&particles, &mut all);
+        ChargeInteraction2D::new(1., 0., Some(1.5)).apply(&particles, &mut near);
+
+        // pushed away by its neighbour, pulled by the opposite charge
+        assert!(all[0].x < 0.);
+        assert_eq!(near[0], Vector2D::new(-1., 0.));
+        assert_eq!(near[2], Vector2D::new(-1., 0.));
+        assert_eq!(near[3], Vector2D::zero());
+        assert!(all[3].x > 0.);
+    }
+}
@@ -9,6 +9,7 @@ pub struct ConstantForceBehavior2D {
 
 impl ConstantForceBehavior2D {
     #[inline]
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(force_x: f32, force_y: f32) -> Box<dyn ParticleBehaviour2D> {
         Box::new(ConstantForceBehavior2D {
             force: Vector2D::new(force_x, force_y),
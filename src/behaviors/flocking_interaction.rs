@@ -0,0 +1,130 @@
+use super::{InteractionParticle2D, ParticleInteraction2D};
+use crate::{SpatialHash2D, Vector2D};
+
+/// Short range cohesion and separation between neighbouring particles.
+///
+/// Every particle is pulled towards the centre of its neighbours within
+/// `radius` by `cohesion`, and pushed away from neighbours closer than
+/// `separation_radius` by `separation`, more strongly the closer they are.
+/// Both are accelerations, turned into forces by the particle's weight, so
+/// heavy particles steer as quickly as light ones.
+pub struct FlockingInteraction2D {
+    radius: f32,
+    cohesion: f32,
+    separation: f32,
+    separation_radius: f32,
+    timestep: f32,
+}
+
+impl FlockingInteraction2D {
+    #[inline]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        radius: f32,
+        cohesion: f32,
+        separation: f32,
+        separation_radius: f32,
+    ) -> Box<dyn ParticleInteraction2D> {
+        Box::new(FlockingInteraction2D {
+            radius,
+            cohesion,
+            separation,
+            separation_radius,
+            timestep: 1.0,
+        })
+    }
+}
+
+impl ParticleInteraction2D for FlockingInteraction2D {
+    fn apply(&self, particles: &[InteractionParticle2D], forces: &mut [Vector2D]) {
+        if self.radius <= 0. {
+            return;
+        }
+        let mut grid = SpatialHash2D::new(self.radius);
+        for (i, p) in particles.iter().enumerate() {
+            grid.insert(i, &p.position, 0.);
+        }
+
+        for (i, p) in particles.iter().enumerate() {
+            let mut center = Vector2D::zero();
+            let mut neighbours = 0;
+            let mut push = Vector2D::zero();
+            for j in grid.query_radius(&p.position, self.radius) {
+                if j == i {
+                    continue;
+                }
+                let other = particles[j].position;
+                center += other;
+                neighbours += 1;
+
+                let away = p.position - other;
+                let dist = away.magnitude();
+                if dist > 0. && dist < self.separation_radius {
+                    push += away * ((1. - dist / self.separation_radius) / dist);
+                }
+            }
+            if neighbours == 0 {
+                continue;
+            }
+
+            let cohesion = (center / neighbours as f32 - p.position) * self.cohesion;
+            forces[i] += (cohesion + push * self.separation) * (p.weight * self.timestep);
+        }
+    }
+
+    fn configure(&mut self, timestep: f32) {
+        self.timestep = timestep;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bird(x: f32, weight: f32) -> InteractionParticle2D {
+        InteractionParticle2D {
+            object: Default::default(),
+            particle: Default::default(),
+            position: Vector2D::new(x, 0.),
+            weight,
+            charge: 0.,
+        }
+    }
+
+    #[test]
+    fn test_flock_coheres_and_separates() {
+        let flock = FlockingInteraction2D::new(5., 0.1, 1., 1.);
+        let birds = [bird(0., 1.), bird(0.5, 1.), bird(4., 2.), bird(20., 1.)];
+        let mut forces = [Vector2D::zero(); 4];
+        flock.apply(&birds, &mut forces);
+
+        // too close to its neighbour, pushed apart
+        assert!(forces[0].x < 0.);
+        // pulled towards the others, as a force scaled by its weight
+        assert!((forces[2].x - 2. * 0.1 * (0.25 - 4.)).abs() < 1e-5);
+        // too far from everyone
+        assert_eq!(forces[3], Vector2D::zero());
+    }
+
+    #[test]
+    fn test_heavy_and_light_birds_steer_alike() {
+        use crate::{VerletObject2D, VerletPhysics2D};
+
+        let mut world = VerletPhysics2D::new_with(1.0, 1, 0.0, None);
+        world.add_interaction(FlockingInteraction2D::new(5., 0.1, 0., 0.));
+        let mut object = VerletObject2D::new("flock");
+        let light = object.create_particle(&Vector2D::new(-1., 0.));
+        let heavy = object.create_particle(&Vector2D::new(1., 0.));
+        object.get_particle_mut(heavy).set_weight(4.);
+        let id = world.add_verlet_object(object);
+        world.update();
+
+        let object = world.get_verlet_object(id);
+        let velocity = |k| {
+            let p = object.get_particle(k);
+            *p.get_position() - *p.get_last_position()
+        };
+        assert!((velocity(light).x - 0.2).abs() < 1e-5);
+        assert!((velocity(heavy).x + 0.2).abs() < 1e-5);
+    }
+}
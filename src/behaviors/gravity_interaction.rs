@@ -0,0 +1,145 @@
+use super::barnes_hut::QuadTree;
+use super::{InteractionParticle2D, ParticleInteraction2D};
+use crate::Vector2D;
+
+/// Inverse-square attraction between every pair of particles, weighted by
+/// their weights.
+///
+/// `softening` is added to the distance so close particles do not get
+/// infinite forces. With Barnes–Hut, groups of particles that look smaller
+/// than `theta` are treated as one, which trades accuracy for speed on large
+/// particle counts.
+pub struct GravityInteraction2D {
+    strength: f32,
+    softening: f32,
+    theta: Option<f32>,
+    timestep: f32,
+}
+
+impl GravityInteraction2D {
+    #[inline]
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(strength: f32, softening: f32) -> Box<dyn ParticleInteraction2D> {
+        Box::new(GravityInteraction2D {
+            strength,
+            softening,
+            theta: None,
+            timestep: 1.0,
+        })
+    }
+
+    /// Gravity using the Barnes–Hut approximation, `theta` around `0.5`
+    /// is a common choice
+    pub fn with_barnes_hut(
+        strength: f32,
+        softening: f32,
+        theta: f32,
+    ) -> Box<dyn ParticleInteraction2D> {
+        Box::new(GravityInteraction2D {
+            strength,
+            softening,
+            theta: Some(theta),
+            timestep: 1.0,
+        })
+    }
+
+    /// Force on a particle of `weight` from `mass` at `delta` from it
+    #[inline]
+    fn force(&self, delta: Vector2D, weight: f32, mass: f32) -> Vector2D {
+        let dist_sq = delta.magnitude_sq() + self.softening * self.softening;
+        if dist_sq == 0. {
+            return Vector2D::zero();
+        }
+        delta * (self.strength * weight * mass / (dist_sq * dist_sq.sqrt()))
+    }
+}
+
+impl ParticleInteraction2D for GravityInteraction2D {
+    fn apply(&self, particles: &[InteractionParticle2D], forces: &mut [Vector2D]) {
+        match self.theta {
+            Some(theta) => {
+                let tree = QuadTree::new(particles);
+                for (i, p) in particles.iter().enumerate() {
+                    let f = tree.accumulate(particles, i, theta, |delta, mass| {
+                        self.force(delta, p.weight, mass)
+                    });
+                    forces[i] += f * self.timestep;
+                }
+            }
+            None => {
+                for i in 0..particles.len() {
+                    for j in (i + 1)..particles.len() {
+                        let (a, b) = (&particles[i], &particles[j]);
+                        let f =
+                            self.force(b.position - a.position, a.weight, b.weight) * self.timestep;
+                        forces[i] += f;
+                        forces[j] -= f;
+                    }
+                }
+            }
+        }
+    }
+
+    fn configure(&mut self, timestep: f32) {
+        self.timestep = timestep;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(n: usize) -> Vec<InteractionParticle2D> {
+        (0..n)
+            .map(|i| {
+                let angle = i as f32 * 2.4;
+                let r = (i as f32).sqrt() * 3.;
+                InteractionParticle2D {
+                    object: Default::default(),
+                    particle: Default::default(),
+                    position: Vector2D::new(r * angle.cos(), r * angle.sin()),
+                    weight: 1. + (i % 3) as f32,
+                    charge: 0.,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_barnes_hut_matches_direct_sum() {
+        let particles = cloud(200);
+        let mut direct = vec![Vector2D::zero(); particles.len()];
+        let mut approx = vec![Vector2D::zero(); particles.len()];
+        GravityInteraction2D::new(1., 0.5).apply(&particles, &mut direct);
+        GravityInteraction2D::with_barnes_hut(1., 0.5, 0.3).apply(&particles, &mut approx);
+
+        for (d, a) in direct.iter().zip(approx.iter()) {
+            assert!((*d - *a).magnitude() <= 0.05 * d.magnitude() + 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_gravity_conserves_momentum() {
+        use crate::{VerletObject2D, VerletPhysics2D};
+
+        let mut world = VerletPhysics2D::new_with(1.0, 1, 0.0, None);
+        world.add_interaction(GravityInteraction2D::new(1., 0.5));
+        let mut object = VerletObject2D::new("pair");
+        let light = object.create_particle(&Vector2D::new(0., 0.));
+        let heavy = object.create_particle(&Vector2D::new(10., 0.));
+        object.get_particle_mut(heavy).set_weight(4.);
+        let id = world.add_verlet_object(object);
+
+        for _ in 0..20 {
+            world.update();
+        }
+        let object = world.get_verlet_object(id);
+        let momentum = |k| {
+            let p = object.get_particle(k);
+            (*p.get_position() - *p.get_last_position()) * p.get_weight()
+        };
+        let total = momentum(light) + momentum(heavy);
+        assert!(momentum(light).x > 0.);
+        assert!(total.magnitude() < 1e-4 * momentum(light).magnitude());
+    }
+}
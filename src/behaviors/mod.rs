@@ -1,11 +1,53 @@
+mod attraction_behavior;
+mod barnes_hut;
+mod behavior_scope;
+mod charge_interaction;
 mod constant_force_behavior;
+mod flocking_interaction;
+mod gravity_interaction;
 
-use crate::Particle2D;
+use crate::{ObjectKey, Particle2D, ParticleKey, Vector2D};
 
+/// Acts on each particle in its scope on its own, like a constant force or
+/// an attractor.
+///
+/// Forces are added with `Particle2D::add_force` and change a particle's
+/// velocity by the force times its weight, so under the same force heavy
+/// particles move faster than light ones.
 pub trait ParticleBehaviour2D {
     fn apply(&self, p: &mut Particle2D);
 
     fn configure(&mut self, timestep: f32);
 }
 
+/// State of a particle as seen by a [`ParticleInteraction2D`], taken at the
+/// start of each engine update
+#[derive(Clone, Copy, Debug)]
+pub struct InteractionParticle2D {
+    pub object: ObjectKey,
+    pub particle: ParticleKey,
+    pub position: Vector2D,
+    pub weight: f32,
+    pub charge: f32,
+}
+
+/// A force between pairs of particles, like gravity or charge.
+///
+/// `apply` receives every particle in the interaction's scope and adds the
+/// force on `particles[i]` to `forces[i]`. Unlike behaviour forces these are
+/// physical and applied with `Particle2D::add_physical_force`, a particle's
+/// velocity changes by the force divided by its weight, so equal and
+/// opposite forces on a pair keep their total momentum. To move every
+/// particle alike, multiply an acceleration by the particle's weight.
+pub trait ParticleInteraction2D {
+    fn apply(&self, particles: &[InteractionParticle2D], forces: &mut [Vector2D]);
+
+    fn configure(&mut self, timestep: f32);
+}
+
+pub use attraction_behavior::{AttractionBehavior2D, Falloff2D};
+pub use behavior_scope::BehaviorScope2D;
+pub use charge_interaction::ChargeInteraction2D;
 pub use constant_force_behavior::ConstantForceBehavior2D;
+pub use flocking_interaction::FlockingInteraction2D;
+pub use gravity_interaction::GravityInteraction2D;
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::Vector2D;
+
+/// Uniform grid spatial hash for finding items near a point or region.
+///
+/// Items are inserted with their bounds and are stored in every cell they
+/// overlap, so queries only look at items in nearby cells. The grid is
+/// meant to be cleared and refilled every step.
+pub struct SpatialHash2D<T: Copy> {
+    cell_size: f32,
+    inv_cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    items: Vec<(T, Vector2D, Vector2D)>,
+}
+
+impl<T: Copy> SpatialHash2D<T> {
+    /// Panics if `cell_size` is not a positive finite number
+    pub fn new(cell_size: f32) -> Self {
+        check_cell_size(cell_size);
+        SpatialHash2D {
+            cell_size,
+            inv_cell_size: 1. / cell_size,
+            cells: HashMap::new(),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn get_cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Change the cell size, this clears the grid
+    ///
+    /// Panics if `cell_size` is not a positive finite number
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        check_cell_size(cell_size);
+        self.cell_size = cell_size;
+        self.inv_cell_size = 1. / self.cell_size;
+        self.clear();
+    }
+
+    pub fn clear(&mut self) {
+        if self.cells.len() > 4 * self.items.len() + 1024 {
+            // too many stale cells left behind by moving items
+            self.cells.clear();
+        } else {
+            // keep the cell vectors around to avoid reallocating every step
+            for cell in self.cells.values_mut() {
+                cell.clear();
+            }
+        }
+        self.items.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Insert an item covering a circle of `radius` around `position`
+    pub fn insert(&mut self, item: T, position: &Vector2D, radius: f32) {
+        let r = Vector2D::new(radius, radius);
+        self.insert_aabb(item, &(*position - r), &(*position + r));
+    }
+
+    /// Insert an item covering the box between `min` and `max`
+    pub fn insert_aabb(&mut self, item: T, min: &Vector2D, max: &Vector2D) {
+        let index = self.items.len();
+        self.items.push((item, *min, *max));
+
+        let (x0, y0) = self.cell_of(min);
+        let (x1, y1) = self.cell_of(max);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    /// All items whose bounds overlap the box between `min` and `max`
+    pub fn query_aabb(&self, min: &Vector2D, max: &Vector2D) -> Vec<T> {
+        let mut indices = Vec::new();
+        self.query_indices(min, max, &mut indices);
+        indices.into_iter().map(|i| self.items[i].0).collect()
+    }
+
+    /// All items whose bounds are within `radius` of `center`
+    pub fn query_radius(&self, center: &Vector2D, radius: f32) -> Vec<T> {
+        let r = Vector2D::new(radius, radius);
+        let mut indices = Vec::new();
+        self.query_indices(&(*center - r), &(*center + r), &mut indices);
+
+        let radius_sq = radius * radius;
+        indices
+            .into_iter()
+            .filter(|i| {
+                let (_, min, max) = &self.items[*i];
+                let closest =
+                    Vector2D::new(center.x.clamp(min.x, max.x), center.y.clamp(min.y, max.y));
+                (*center - closest).magnitude_sq() <= radius_sq
+            })
+            .map(|i| self.items[i].0)
+            .collect()
+    }
+
+    /// Item at `index`, in insertion order
+    pub(crate) fn get_item(&self, index: usize) -> T {
+        self.items[index].0
+    }
+
+    /// Indices of items whose bounds overlap the box, sorted and without
+    /// duplicates
+    pub(crate) fn query_indices(&self, min: &Vector2D, max: &Vector2D, out: &mut Vec<usize>) {
+        out.clear();
+        let (x0, y0) = self.cell_of(min);
+        let (x1, y1) = self.cell_of(max);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    out.extend(cell.iter().filter(|i| {
+                        let (_, item_min, item_max) = &self.items[**i];
+                        item_min.x <= max.x
+                            && item_max.x >= min.x
+                            && item_min.y <= max.y
+                            && item_max.y >= min.y
+                    }));
+                }
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+
+    #[inline]
+    fn cell_of(&self, p: &Vector2D) -> (i32, i32) {
+        (
+            (p.x * self.inv_cell_size).floor() as i32,
+            (p.y * self.inv_cell_size).floor() as i32,
+        )
+    }
+}
+
+/// Tiny cells would make every insert and query walk a huge number of them
+fn check_cell_size(cell_size: f32) {
+    if !(cell_size.is_finite() && cell_size > 0.) {
+        panic!("cell size must be positive and finite, got {}", cell_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spatial_hash_query_radius() {
+        let mut grid = SpatialHash2D::new(10.);
+        grid.insert(1, &Vector2D::new(5., 5.), 0.);
+        grid.insert(2, &Vector2D::new(12., 5.), 0.);
+        grid.insert(3, &Vector2D::new(50., 50.), 0.);
+
+        let mut found = grid.query_radius(&Vector2D::new(6., 5.), 7.);
+        found.sort();
+        assert_eq!(found, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_spatial_hash_no_duplicates() {
+        let mut grid = SpatialHash2D::new(1.);
+        grid.insert_aabb(7, &Vector2D::new(0., 0.), &Vector2D::new(5., 5.));
+        assert_eq!(
+            grid.query_aabb(&Vector2D::new(-1., -1.), &Vector2D::new(6., 6.)),
+            vec![7]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cell size must be positive")]
+    fn test_spatial_hash_rejects_zero_cell_size() {
+        SpatialHash2D::<usize>::new(0.);
+    }
+}
@@ -0,0 +1,31 @@
+/// Layer and mask bits deciding what collides with what.
+///
+/// Two things collide when each one's layers overlap the other's mask. The
+/// default filter is on layer 1 and collides with every layer.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CollisionFilter2D {
+    pub layers: u32,
+    pub mask: u32,
+}
+
+impl CollisionFilter2D {
+    pub fn new(layers: u32, mask: u32) -> Self {
+        CollisionFilter2D { layers, mask }
+    }
+
+    /// Filter that collides with nothing
+    pub fn none() -> Self {
+        CollisionFilter2D::new(0, 0)
+    }
+
+    #[inline]
+    pub fn collides_with(&self, other: &CollisionFilter2D) -> bool {
+        (self.layers & other.mask) != 0 && (other.layers & self.mask) != 0
+    }
+}
+
+impl Default for CollisionFilter2D {
+    fn default() -> Self {
+        CollisionFilter2D::new(1, u32::MAX)
+    }
+}
@@ -0,0 +1,93 @@
+/// How the material values of two touching things are combined.
+///
+/// When the two sides use different rules, the one listed later wins, so
+/// `Max` beats `Multiply`, which beats `Min`, which beats `Average`.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum CombineRule2D {
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineRule2D {
+    #[inline]
+    pub fn combine(&self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineRule2D::Average => (a + b) / 2.,
+            CombineRule2D::Min => a.min(b),
+            CombineRule2D::Multiply => a * b,
+            CombineRule2D::Max => a.max(b),
+        }
+    }
+}
+
+/// Surface properties used when particles touch colliders.
+///
+/// Friction is a coefficient relative to how hard the particle is pressed
+/// against the surface: a particle sticks while its sliding speed is below
+/// `static_friction` times that, and slows by `kinetic_friction` times that
+/// when sliding. `restitution` is the fraction of the approach speed kept
+/// when bouncing off, from 0 (no bounce) to 1.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Material2D {
+    pub static_friction: f32,
+    pub kinetic_friction: f32,
+    pub restitution: f32,
+    pub friction_combine: CombineRule2D,
+    pub restitution_combine: CombineRule2D,
+}
+
+impl Material2D {
+    pub fn new(static_friction: f32, kinetic_friction: f32, restitution: f32) -> Self {
+        Material2D {
+            static_friction,
+            kinetic_friction,
+            restitution,
+            friction_combine: CombineRule2D::Average,
+            restitution_combine: CombineRule2D::Average,
+        }
+    }
+
+    /// Combined `(static_friction, kinetic_friction, restitution)` of two
+    /// materials in contact
+    pub fn combine(&self, other: &Material2D) -> (f32, f32, f32) {
+        let friction_rule = if self.friction_combine > other.friction_combine {
+            self.friction_combine
+        } else {
+            other.friction_combine
+        };
+        let restitution_rule = if self.restitution_combine > other.restitution_combine {
+            self.restitution_combine
+        } else {
+            other.restitution_combine
+        };
+        (
+            friction_rule.combine(self.static_friction, other.static_friction),
+            friction_rule.combine(self.kinetic_friction, other.kinetic_friction),
+            restitution_rule.combine(self.restitution, other.restitution),
+        )
+    }
+}
+
+impl Default for Material2D {
+    fn default() -> Self {
+        Material2D::new(0., 0., 0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_uses_the_stronger_rule() {
+        let mut rubber = Material2D::new(1., 0.8, 0.9);
+        let ice = Material2D::new(0.1, 0.05, 0.1);
+        assert_eq!(rubber.combine(&ice), (0.55, 0.425, 0.5));
+
+        rubber.restitution_combine = CombineRule2D::Max;
+        rubber.friction_combine = CombineRule2D::Min;
+        assert_eq!(ice.combine(&rubber), (0.1, 0.05, 0.9));
+    }
+}
@@ -0,0 +1,64 @@
+mod filter;
+mod material;
+mod sensor;
+mod shape;
+
+use slotmap::new_key_type;
+
+pub use filter::CollisionFilter2D;
+pub use material::{CombineRule2D, Material2D};
+pub use sensor::Sensor2D;
+pub use shape::Shape2D;
+pub(crate) use shape::{
+    closest_point_on_segment, closest_points_between_segments, segment_intersection,
+};
+
+new_key_type! { pub struct ColliderKey; }
+new_key_type! { pub struct SensorKey; }
+
+/// A static, solid shape that particles cannot enter.
+///
+/// Colliders are added to [`VerletPhysics2D`](crate::VerletPhysics2D) and
+/// push particles out, taking the particle radius into account.
+pub struct Collider2D {
+    shape: Shape2D,
+    filter: CollisionFilter2D,
+    material: Material2D,
+}
+
+impl Collider2D {
+    pub fn new(shape: Shape2D) -> Self {
+        Collider2D {
+            shape,
+            filter: CollisionFilter2D::default(),
+            material: Material2D::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_shape(&self) -> &Shape2D {
+        &self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: Shape2D) {
+        self.shape = shape;
+    }
+
+    pub fn set_collision_filter(&mut self, filter: CollisionFilter2D) {
+        self.filter = filter;
+    }
+
+    #[inline(always)]
+    pub fn get_collision_filter(&self) -> CollisionFilter2D {
+        self.filter
+    }
+
+    pub fn set_material(&mut self, material: Material2D) {
+        self.material = material;
+    }
+
+    #[inline(always)]
+    pub fn get_material(&self) -> &Material2D {
+        &self.material
+    }
+}
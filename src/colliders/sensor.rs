@@ -0,0 +1,38 @@
+use super::{CollisionFilter2D, Shape2D};
+
+/// A region that reports the particles and objects inside it without
+/// pushing them.
+///
+/// Sensors are added to [`VerletPhysics2D`](crate::VerletPhysics2D), which
+/// reports enter, stay and exit events for them after every step.
+pub struct Sensor2D {
+    shape: Shape2D,
+    filter: CollisionFilter2D,
+}
+
+impl Sensor2D {
+    pub fn new(shape: Shape2D) -> Self {
+        Sensor2D {
+            shape,
+            filter: CollisionFilter2D::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_shape(&self) -> &Shape2D {
+        &self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: Shape2D) {
+        self.shape = shape;
+    }
+
+    pub fn set_collision_filter(&mut self, filter: CollisionFilter2D) {
+        self.filter = filter;
+    }
+
+    #[inline(always)]
+    pub fn get_collision_filter(&self) -> CollisionFilter2D {
+        self.filter
+    }
+}
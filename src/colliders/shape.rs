@@ -0,0 +1,410 @@
+use crate::Vector2D;
+
+/// Geometry of a static collider.
+///
+/// All shapes are solid, except segments which are infinitely thin and are
+/// only thickened by the particle radius.
+#[derive(Clone)]
+pub enum Shape2D {
+    Segment {
+        start: Vector2D,
+        end: Vector2D,
+    },
+    Circle {
+        center: Vector2D,
+        radius: f32,
+    },
+    /// Box with `half_extents` around `center`, rotated by `rotation` radians
+    Box {
+        center: Vector2D,
+        half_extents: Vector2D,
+        rotation: f32,
+    },
+    /// Convex polygon with counter-clockwise vertices
+    Polygon {
+        vertices: Vec<Vector2D>,
+    },
+}
+
+impl Shape2D {
+    pub fn segment(start: &Vector2D, end: &Vector2D) -> Shape2D {
+        Shape2D::Segment {
+            start: *start,
+            end: *end,
+        }
+    }
+
+    pub fn circle(center: &Vector2D, radius: f32) -> Shape2D {
+        Shape2D::Circle {
+            center: *center,
+            radius,
+        }
+    }
+
+    /// Axis-aligned box between the `min` and `max` corners
+    pub fn aabb(min: &Vector2D, max: &Vector2D) -> Shape2D {
+        Shape2D::Box {
+            center: (*min + *max) / 2.,
+            half_extents: Vector2D::new((max.x - min.x).abs() / 2., (max.y - min.y).abs() / 2.),
+            rotation: 0.,
+        }
+    }
+
+    /// Oriented box with `half_extents` around `center`, rotated by
+    /// `rotation` radians
+    pub fn obb(center: &Vector2D, half_extents: &Vector2D, rotation: f32) -> Shape2D {
+        Shape2D::Box {
+            center: *center,
+            half_extents: Vector2D::new(half_extents.x.abs(), half_extents.y.abs()),
+            rotation,
+        }
+    }
+
+    /// Convex polygon, vertices may be given in either winding order
+    pub fn polygon(vertices: &[Vector2D]) -> Shape2D {
+        let mut vertices = vertices.to_vec();
+        let n = vertices.len();
+        let area: f32 = (0..n)
+            .map(|i| vertices[i].cross(vertices[(i + 1) % n]))
+            .sum();
+        if area < 0. {
+            vertices.reverse();
+        }
+        Shape2D::Polygon { vertices }
+    }
+
+    /// Normal and depth to push a circle of `radius` at `point` out of the
+    /// shape, or `None` if they do not overlap
+    pub fn penetration(&self, point: &Vector2D, radius: f32) -> Option<(Vector2D, f32)> {
+        match self {
+            Shape2D::Segment { start, end } => {
+                let closest = closest_point_on_segment(point, start, end);
+                let fallback = (*end - *start).perpendicular().normalize();
+                push_from_point(point, &closest, radius, fallback)
+            }
+            Shape2D::Circle {
+                center,
+                radius: shape_radius,
+            } => {
+                let (n, depth) =
+                    push_from_point(point, center, radius + shape_radius, Vector2D::new(0., -1.))?;
+                Some((n, depth))
+            }
+            Shape2D::Box {
+                center,
+                half_extents,
+                rotation,
+            } => {
+                let local = (*point - *center).rotate(-rotation);
+                let (n, depth) = box_penetration(&local, half_extents, radius)?;
+                Some((n.rotate(*rotation), depth))
+            }
+            Shape2D::Polygon { vertices } => polygon_penetration(vertices, point, radius),
+        }
+    }
+
+    /// First contact of a circle of `radius` moving from `from` to `to`
+    /// with the shape, as the fraction of the way travelled and the contact
+    /// normal. Circles already overlapping the shape at `from` are not
+    /// reported.
+    pub fn sweep(&self, from: &Vector2D, to: &Vector2D, radius: f32) -> Option<(f32, Vector2D)> {
+        let dir = *to - *from;
+        match self {
+            Shape2D::Circle {
+                center,
+                radius: shape_radius,
+            } => sweep_circle(from, &dir, center, radius + shape_radius),
+            Shape2D::Segment { start, end } => {
+                sweep_rounded_polygon(&[*start, *end], from, &dir, radius)
+            }
+            Shape2D::Box {
+                center,
+                half_extents,
+                rotation,
+            } => {
+                let (hx, hy) = (half_extents.x, half_extents.y);
+                let corners: Vec<Vector2D> = [(-hx, -hy), (hx, -hy), (hx, hy), (-hx, hy)]
+                    .iter()
+                    .map(|(x, y)| *center + Vector2D::new(*x, *y).rotate(*rotation))
+                    .collect();
+                sweep_rounded_polygon(&corners, from, &dir, radius)
+            }
+            Shape2D::Polygon { vertices } => sweep_rounded_polygon(vertices, from, &dir, radius),
+        }
+    }
+}
+
+/// Fraction along `dir` where the ray from `origin` enters the circle, and
+/// the normal there
+fn sweep_circle(
+    origin: &Vector2D,
+    dir: &Vector2D,
+    center: &Vector2D,
+    radius: f32,
+) -> Option<(f32, Vector2D)> {
+    let m = *origin - *center;
+    let a = dir.magnitude_sq();
+    let b = m.dot(*dir);
+    let c = m.magnitude_sq() - radius * radius;
+    if a == 0. || c <= 0. || b >= 0. {
+        // not moving, already inside or moving away
+        return None;
+    }
+    let disc = b * b - a * c;
+    if disc < 0. {
+        return None;
+    }
+    let t = (-b - disc.sqrt()) / a;
+    if !(0. ..=1.).contains(&t) {
+        return None;
+    }
+    let hit = *origin + *dir * t;
+    Some((t, (hit - *center).normalize()))
+}
+
+/// Fraction along `dir` where the ray from `origin` first touches a
+/// polygon (or segment, for two vertices) grown by `radius`
+fn sweep_rounded_polygon(
+    vertices: &[Vector2D],
+    origin: &Vector2D,
+    dir: &Vector2D,
+    radius: f32,
+) -> Option<(f32, Vector2D)> {
+    let n = vertices.len();
+    let mut best: Option<(f32, Vector2D)> = None;
+    let mut consider = |hit: Option<(f32, Vector2D)>| {
+        if let Some((t, normal)) = hit {
+            if best.is_none_or(|(best_t, _)| t < best_t) {
+                best = Some((t, normal));
+            }
+        }
+    };
+
+    for i in 0..n {
+        let a = vertices[i];
+        let b = vertices[(i + 1) % n];
+        let outward = (b - a).perpendicular().normalize() * -1.;
+        // only faces the ray moves into can be hit, and only from outside,
+        // overlaps at the start are resolved by `penetration`
+        if outward.dot(*dir) < 0. && outward.dot(*origin - a) >= radius {
+            let offset = outward * radius;
+            consider(
+                segment_intersection(origin, dir, &(a + offset), &(b + offset))
+                    .map(|t| (t, outward)),
+            );
+        }
+        if radius > 0. {
+            consider(sweep_circle(origin, dir, &a, radius));
+        }
+    }
+    best
+}
+
+/// Fraction along `dir` where the ray from `origin` crosses segment `a b`
+pub(crate) fn segment_intersection(
+    origin: &Vector2D,
+    dir: &Vector2D,
+    a: &Vector2D,
+    b: &Vector2D,
+) -> Option<f32> {
+    let edge = *b - *a;
+    let denom = dir.cross(edge);
+    if denom == 0. {
+        return None;
+    }
+    let to_a = *a - *origin;
+    let t = to_a.cross(edge) / denom;
+    let u = to_a.cross(*dir) / denom;
+    if (0. ..=1.).contains(&t) && (0. ..=1.).contains(&u) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn closest_point_on_segment(
+    point: &Vector2D,
+    start: &Vector2D,
+    end: &Vector2D,
+) -> Vector2D {
+    let dir = *end - *start;
+    let len_sq = dir.magnitude_sq();
+    if len_sq == 0. {
+        return *start;
+    }
+    let t = ((*point - *start).dot(dir) / len_sq).clamp(0., 1.);
+    *start + dir * t
+}
+
+/// Closest points between segments `p1 q1` and `p2 q2`, as the parameters
+/// along each segment
+pub(crate) fn closest_points_between_segments(
+    p1: &Vector2D,
+    q1: &Vector2D,
+    p2: &Vector2D,
+    q2: &Vector2D,
+) -> (f32, f32) {
+    let d1 = *q1 - *p1;
+    let d2 = *q2 - *p2;
+    let r = *p1 - *p2;
+    let a = d1.magnitude_sq();
+    let e = d2.magnitude_sq();
+    let f = d2.dot(r);
+
+    if a <= f32::EPSILON && e <= f32::EPSILON {
+        return (0., 0.);
+    }
+    if a <= f32::EPSILON {
+        return (0., (f / e).clamp(0., 1.));
+    }
+    let c = d1.dot(r);
+    if e <= f32::EPSILON {
+        return ((-c / a).clamp(0., 1.), 0.);
+    }
+
+    let b = d1.dot(d2);
+    let denom = a * e - b * b;
+    let mut s = if denom != 0. {
+        ((b * f - c * e) / denom).clamp(0., 1.)
+    } else {
+        0.
+    };
+    let mut t = (b * s + f) / e;
+    if t < 0. {
+        t = 0.;
+        s = (-c / a).clamp(0., 1.);
+    } else if t > 1. {
+        t = 1.;
+        s = ((b - c) / a).clamp(0., 1.);
+    }
+    (s, t)
+}
+
+/// Push `point` away from `closest` until they are `radius` apart
+fn push_from_point(
+    point: &Vector2D,
+    closest: &Vector2D,
+    radius: f32,
+    fallback: Vector2D,
+) -> Option<(Vector2D, f32)> {
+    let d = *point - *closest;
+    let dist_sq = d.magnitude_sq();
+    if dist_sq >= radius * radius {
+        return None;
+    }
+    let dist = dist_sq.sqrt();
+    let n = if dist > 0. { d / dist } else { fallback };
+    Some((n, radius - dist))
+}
+
+fn box_penetration(
+    local: &Vector2D,
+    half_extents: &Vector2D,
+    radius: f32,
+) -> Option<(Vector2D, f32)> {
+    let dx = half_extents.x - local.x.abs();
+    let dy = half_extents.y - local.y.abs();
+
+    if dx >= 0. && dy >= 0. {
+        // inside, push out through the closest face
+        return if dx < dy {
+            Some((Vector2D::new(local.x.signum(), 0.), dx + radius))
+        } else {
+            Some((Vector2D::new(0., local.y.signum()), dy + radius))
+        };
+    }
+
+    let closest = Vector2D::new(
+        local.x.clamp(-half_extents.x, half_extents.x),
+        local.y.clamp(-half_extents.y, half_extents.y),
+    );
+    push_from_point(local, &closest, radius, Vector2D::zero())
+}
+
+fn polygon_penetration(
+    vertices: &[Vector2D],
+    point: &Vector2D,
+    radius: f32,
+) -> Option<(Vector2D, f32)> {
+    let n = vertices.len();
+    if n < 3 {
+        return None;
+    }
+
+    let mut max_separation = f32::MIN;
+    let mut max_normal = Vector2D::zero();
+    for i in 0..n {
+        let a = vertices[i];
+        let b = vertices[(i + 1) % n];
+        let normal = (b - a).perpendicular().normalize() * -1.;
+        let separation = normal.dot(*point - a);
+        if separation > max_separation {
+            max_separation = separation;
+            max_normal = normal;
+        }
+    }
+
+    if max_separation <= 0. {
+        // inside, push out through the closest edge
+        return Some((max_normal, radius - max_separation));
+    }
+    if max_separation >= radius {
+        return None;
+    }
+
+    let mut closest = vertices[0];
+    let mut closest_dist_sq = f32::MAX;
+    for i in 0..n {
+        let c = closest_point_on_segment(point, &vertices[i], &vertices[(i + 1) % n]);
+        let dist_sq = (*point - c).magnitude_sq();
+        if dist_sq < closest_dist_sq {
+            closest = c;
+            closest_dist_sq = dist_sq;
+        }
+    }
+    push_from_point(point, &closest, radius, max_normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape2d_circle_penetration() {
+        let shape = Shape2D::circle(&Vector2D::new(0., 0.), 2.);
+        let (n, depth) = shape.penetration(&Vector2D::new(2.5, 0.), 1.).unwrap();
+        assert_eq!(n.x, 1.);
+        assert_eq!(depth, 0.5);
+        assert!(shape.penetration(&Vector2D::new(4., 0.), 1.).is_none());
+    }
+
+    #[test]
+    fn test_shape2d_box_penetration() {
+        let shape = Shape2D::aabb(&Vector2D::new(0., 0.), &Vector2D::new(10., 4.));
+        let (n, depth) = shape.penetration(&Vector2D::new(5., 1.), 0.).unwrap();
+        assert_eq!(n.y, -1.);
+        assert_eq!(depth, 1.);
+    }
+
+    #[test]
+    fn test_shape2d_polygon_penetration() {
+        let shape = Shape2D::polygon(&[
+            Vector2D::new(0., 0.),
+            Vector2D::new(0., 4.),
+            Vector2D::new(4., 0.),
+        ]);
+        let (n, depth) = shape.penetration(&Vector2D::new(1., 0.5), 0.).unwrap();
+        assert_eq!(n.y, -1.);
+        assert_eq!(depth, 0.5);
+    }
+
+    #[test]
+    fn test_shape2d_segment_sweep() {
+        let shape = Shape2D::segment(&Vector2D::new(-10., 0.), &Vector2D::new(10., 0.));
+        let (t, n) = shape
+            .sweep(&Vector2D::new(0., -5.), &Vector2D::new(0., 5.), 1.)
+            .unwrap();
+        assert_eq!(t, 0.4);
+        assert_eq!(n.y, -1.);
+    }
+}
@@ -0,0 +1,1037 @@
+use std::collections::{HashMap, HashSet};
+
+use slotmap::SlotMap;
+
+use crate::colliders::{
+    closest_points_between_segments, segment_intersection, Collider2D, ColliderKey,
+    CollisionFilter2D,
+};
+use crate::events::{Contact, ContactTarget2D};
+use crate::{ObjectKey, ParticleKey, SpatialHash2D, Vector2D, VerletObject2D};
+
+/// Snapshot of a particle taking part in particle-particle collisions
+pub(crate) struct CollisionBody {
+    pub object: ObjectKey,
+    pub particle: ParticleKey,
+    pub position: Vector2D,
+    pub last_position: Vector2D,
+    pub radius: f32,
+    pub inv_weight: f32,
+    pub collisions: bool,
+    pub self_collisions: bool,
+    pub edge_collisions: bool,
+    pub filter: CollisionFilter2D,
+}
+
+impl CollisionBody {
+    #[inline]
+    fn is_active(&self) -> bool {
+        self.collisions || self.self_collisions
+    }
+
+    #[inline]
+    fn can_collide(&self, other: &CollisionBody) -> bool {
+        let enabled = if self.object == other.object {
+            self.self_collisions
+        } else {
+            self.collisions && other.collisions
+        };
+        enabled && self.filter.collides_with(&other.filter)
+    }
+}
+
+/// A spring treated as a capsule between two bodies
+#[derive(Clone, Copy)]
+pub(crate) struct CollisionEdge {
+    pub a: usize,
+    pub b: usize,
+}
+
+/// Resolves overlaps between particles and spring edges, within and across
+/// objects.
+///
+/// Candidate pairs are found once per step from the broadphase, then relaxed
+/// on every solver iteration. Positions are copied out of the objects,
+/// pushed apart pair by pair and written back, so particles of different
+/// objects can be moved together.
+#[derive(Default)]
+pub(crate) struct ParticleCollisions {
+    bodies: Vec<CollisionBody>,
+    edges: Vec<CollisionEdge>,
+    pairs: Vec<(usize, usize)>,
+    particle_edge_pairs: Vec<(usize, usize, f32)>,
+    edge_pairs: Vec<(usize, usize)>,
+    pair_contacts: Vec<Option<(Vector2D, f32, f32)>>,
+    particle_edge_contacts: Vec<Option<(Vector2D, f32, f32)>>,
+    edge_contacts: Vec<Option<(Vector2D, f32, f32)>>,
+    edge_grid: Option<SpatialHash2D<usize>>,
+    scratch: Vec<usize>,
+}
+
+impl ParticleCollisions {
+    /// Snapshot the particles in the order of `broadphase` and find the
+    /// pairs that may touch during this step. With `swept` set, particles
+    /// are also paired with the edges they pass on the way from their last
+    /// position.
+    pub fn prepare(
+        &mut self,
+        objects: &SlotMap<ObjectKey, VerletObject2D>,
+        broadphase: &SpatialHash2D<(ObjectKey, ParticleKey)>,
+        default_radius: f32,
+        swept: bool,
+    ) {
+        self.bodies.clear();
+        self.edges.clear();
+        self.pairs.clear();
+        self.particle_edge_pairs.clear();
+        self.edge_pairs.clear();
+        self.pair_contacts.clear();
+        self.particle_edge_contacts.clear();
+        self.edge_contacts.clear();
+        if !objects
+            .values()
+            .any(|o| o.has_collisions() || o.has_self_collisions())
+        {
+            return;
+        }
+
+        let mut max_radius: f32 = 0.;
+        for i in 0..broadphase.len() {
+            let (object_id, particle_id) = broadphase.get_item(i);
+            let o = &objects[object_id];
+            let p = o.get_particle(particle_id);
+            let radius = p.collision_radius(default_radius);
+            let filter = p.effective_filter(o.get_collision_filter());
+            max_radius = max_radius.max(radius);
+            self.bodies.push(CollisionBody {
+                object: object_id,
+                particle: particle_id,
+                position: p.position,
+                last_position: p.last_position,
+                radius,
+                inv_weight: p.inv_weight,
+                collisions: o.has_collisions(),
+                self_collisions: o.has_self_collisions(),
+                edge_collisions: o.has_edge_collisions(),
+                filter,
+            });
+        }
+        // leave room for particles moving while the solver iterates
+        let margin = 2. * max_radius;
+        let connected = connected_particles(objects);
+
+        for i in 0..self.bodies.len() {
+            let a = &self.bodies[i];
+            if !a.is_active() {
+                continue;
+            }
+            let reach = a.radius + margin;
+            let r = Vector2D::new(reach, reach);
+            broadphase.query_indices(&(a.position - r), &(a.position + r), &mut self.scratch);
+            for &j in self.scratch.iter() {
+                let b = &self.bodies[j];
+                if j > i
+                    && a.can_collide(b)
+                    && a.inv_weight + b.inv_weight > 0.
+                    && !is_connected(&connected, a, b)
+                {
+                    self.pairs.push((i, j));
+                }
+            }
+        }
+
+        self.prepare_edges(objects, margin, swept);
+
+        self.pair_contacts.resize(self.pairs.len(), None);
+        self.particle_edge_contacts
+            .resize(self.particle_edge_pairs.len(), None);
+        self.edge_contacts.resize(self.edge_pairs.len(), None);
+    }
+
+    fn prepare_edges(
+        &mut self,
+        objects: &SlotMap<ObjectKey, VerletObject2D>,
+        margin: f32,
+        swept: bool,
+    ) {
+        if !self
+            .bodies
+            .iter()
+            .any(|b| b.is_active() && b.edge_collisions)
+        {
+            return;
+        }
+
+        let mut index = HashMap::new();
+        for (i, b) in self.bodies.iter().enumerate() {
+            if b.is_active() && b.edge_collisions {
+                index.insert((b.object, b.particle), i);
+            }
+        }
+        for (object_id, o) in objects.iter() {
+            if !o.has_edge_collisions() {
+                continue;
+            }
+            for (_, s) in o.enabled_springs() {
+                let a = index.get(&(object_id, s.get_particle_a_id()));
+                let b = index.get(&(object_id, s.get_particle_b_id()));
+                if let (Some(&a), Some(&b)) = (a, b) {
+                    self.edges.push(CollisionEdge { a, b });
+                }
+            }
+        }
+
+        let grid = self.edge_grid.get_or_insert_with(|| SpatialHash2D::new(1.));
+        let cell_size = (4. * margin).max(1.);
+        if grid.get_cell_size() != cell_size {
+            grid.set_cell_size(cell_size);
+        }
+        grid.clear();
+        for (i, e) in self.edges.iter().enumerate() {
+            let (min, max) = edge_bounds(&self.bodies, e, margin);
+            grid.insert_aabb(i, &min, &max);
+        }
+
+        // particles against edges
+        for i in 0..self.bodies.len() {
+            let p = &self.bodies[i];
+            if !p.is_active() {
+                continue;
+            }
+            let reach = p.radius + margin;
+            let r = Vector2D::new(reach, reach);
+            let (mut min, mut max) = (p.position - r, p.position + r);
+            if swept {
+                min = Vector2D::new(
+                    min.x.min(p.last_position.x - reach),
+                    min.y.min(p.last_position.y - reach),
+                );
+                max = Vector2D::new(
+                    max.x.max(p.last_position.x + reach),
+                    max.y.max(p.last_position.y + reach),
+                );
+            }
+            grid.query_indices(&min, &max, &mut self.scratch);
+            for &e in self.scratch.iter() {
+                let edge = self.edges[e];
+                if edge.a != i && edge.b != i && p.can_collide(&self.bodies[edge.a]) {
+                    let side = edge_side(&self.bodies, i, &edge);
+                    self.particle_edge_pairs.push((i, e, side));
+                }
+            }
+        }
+
+        // edges against edges
+        for i in 0..self.edges.len() {
+            let e1 = self.edges[i];
+            let (min, max) = edge_bounds(&self.bodies, &e1, 0.);
+            grid.query_indices(&min, &max, &mut self.scratch);
+            for &j in self.scratch.iter() {
+                let e2 = self.edges[j];
+                let shared = e1.a == e2.a || e1.a == e2.b || e1.b == e2.a || e1.b == e2.b;
+                if j > i && !shared && self.bodies[e1.a].can_collide(&self.bodies[e2.a]) {
+                    self.edge_pairs.push((i, j));
+                }
+            }
+        }
+    }
+
+    /// Stop particles that crossed a spring edge since their last position
+    /// on the side they came from
+    pub fn sweep_edges(&mut self, objects: &mut SlotMap<ObjectKey, VerletObject2D>) {
+        for &(i, e, _) in self.particle_edge_pairs.iter() {
+            let edge = self.edges[e];
+            let (a, b) = (&self.bodies[edge.a], &self.bodies[edge.b]);
+            let p = &self.bodies[i];
+            if p.inv_weight == 0. {
+                continue;
+            }
+
+            // follow the particle relative to the moving edge
+            let translation =
+                ((a.position + b.position) - (a.last_position + b.last_position)) / 2.;
+            let start = p.last_position + translation;
+            let end = p.position;
+            if segment_intersection(&start, &(end - start), &a.position, &b.position).is_none() {
+                continue;
+            }
+
+            let mut n = (b.position - a.position).perpendicular().normalize();
+            if n.dot(start - a.position) < 0. {
+                n = n * -1.;
+            }
+            let min_dist = p.radius + edge_radius(&self.bodies, &edge);
+            let position = end + n * (n.dot(a.position - end) + min_dist);
+
+            let (object_id, particle_id) = (p.object, p.particle);
+            self.bodies[i].position = position;
+            objects[object_id]
+                .get_particle_mut(particle_id)
+                .set_position(&position);
+        }
+    }
+
+    /// Push apart the candidate pairs found by `prepare`
+    pub fn resolve(&mut self, objects: &mut SlotMap<ObjectKey, VerletObject2D>) {
+        if self.pairs.is_empty()
+            && self.particle_edge_pairs.is_empty()
+            && self.edge_pairs.is_empty()
+        {
+            return;
+        }
+
+        for b in self.bodies.iter_mut().filter(|b| b.is_active()) {
+            b.position = *objects[b.object].get_particle(b.particle).get_position();
+        }
+
+        for (k, &(i, j)) in self.pairs.iter().enumerate() {
+            let (left, right) = self.bodies.split_at_mut(j);
+            let hit = solve_pair(&mut left[i], &mut right[0]);
+            accumulate_contact(&mut self.pair_contacts[k], hit);
+        }
+        for (k, &(i, e, side)) in self.particle_edge_pairs.iter().enumerate() {
+            let hit = solve_particle_edge(&mut self.bodies, i, &self.edges[e], side);
+            accumulate_contact(&mut self.particle_edge_contacts[k], hit);
+        }
+        for (k, &(i, j)) in self.edge_pairs.iter().enumerate() {
+            let hit = solve_edge_edge(&mut self.bodies, &self.edges[i], &self.edges[j]);
+            accumulate_contact(&mut self.edge_contacts[k], hit);
+        }
+
+        for b in self.bodies.iter().filter(|b| b.is_active()) {
+            objects[b.object]
+                .get_particle_mut(b.particle)
+                .set_position(&b.position);
+        }
+    }
+}
+
+impl ParticleCollisions {
+    /// Contacts between particles, between particles and spring edges and
+    /// between spring edges found during this step
+    pub fn collect_contacts(&self, out: &mut Vec<Contact>) {
+        for (k, &(i, j)) in self.pairs.iter().enumerate() {
+            if let Some((normal, depth, impulse)) = self.pair_contacts[k] {
+                // reported once for each of the two particles
+                let (a, b) = (&self.bodies[i], &self.bodies[j]);
+                out.push(Contact {
+                    object: a.object,
+                    particle: a.particle,
+                    other: ContactTarget2D::Particle(b.object, b.particle),
+                    normal,
+                    depth,
+                    impulse,
+                });
+                out.push(Contact {
+                    object: b.object,
+                    particle: b.particle,
+                    other: ContactTarget2D::Particle(a.object, a.particle),
+                    normal: normal * -1.,
+                    depth,
+                    impulse,
+                });
+            }
+        }
+        for (k, &(i, e, _)) in self.particle_edge_pairs.iter().enumerate() {
+            if let Some((normal, depth, impulse)) = self.particle_edge_contacts[k] {
+                let p = &self.bodies[i];
+                let (a, b) = (&self.bodies[self.edges[e].a], &self.bodies[self.edges[e].b]);
+                out.push(Contact {
+                    object: p.object,
+                    particle: p.particle,
+                    other: ContactTarget2D::Spring(a.object, a.particle, b.particle),
+                    normal,
+                    depth,
+                    impulse,
+                });
+            }
+        }
+        for (k, &(i, j)) in self.edge_pairs.iter().enumerate() {
+            if let Some((normal, depth, impulse)) = self.edge_contacts[k] {
+                // reported once for each edge, on its particle nearest to
+                // the contact
+                let (e1, e2) = (&self.edges[i], &self.edges[j]);
+                let (s, t) = closest_points_between_segments(
+                    &self.bodies[e1.a].position,
+                    &self.bodies[e1.b].position,
+                    &self.bodies[e2.a].position,
+                    &self.bodies[e2.b].position,
+                );
+                out.push(self.edge_contact(e1, s, e2, normal, depth, impulse));
+                out.push(self.edge_contact(e2, t, e1, normal * -1., depth, impulse));
+            }
+        }
+    }
+
+    fn edge_contact(
+        &self,
+        edge: &CollisionEdge,
+        param: f32,
+        other: &CollisionEdge,
+        normal: Vector2D,
+        depth: f32,
+        impulse: f32,
+    ) -> Contact {
+        let p = &self.bodies[if param < 0.5 { edge.a } else { edge.b }];
+        let (a, b) = (&self.bodies[other.a], &self.bodies[other.b]);
+        Contact {
+            object: p.object,
+            particle: p.particle,
+            other: ContactTarget2D::Spring(a.object, a.particle, b.particle),
+            normal,
+            depth,
+            impulse,
+        }
+    }
+}
+
+/// Keep the deepest penetration and the total impulse over all solver
+/// iterations
+#[inline]
+fn accumulate_contact(
+    contact: &mut Option<(Vector2D, f32, f32)>,
+    hit: Option<(Vector2D, f32, f32)>,
+) {
+    if let Some((normal, depth, impulse)) = hit {
+        *contact = match *contact {
+            Some((_, d, i)) => Some((normal, d.max(depth), i + impulse)),
+            None => Some((normal, depth, impulse)),
+        };
+    }
+}
+
+/// Spring connections of objects that ignore collisions between connected
+/// particles
+fn connected_particles(
+    objects: &SlotMap<ObjectKey, VerletObject2D>,
+) -> HashSet<(ObjectKey, ParticleKey, ParticleKey)> {
+    let mut connected = HashSet::new();
+    for (object_id, o) in objects.iter() {
+        if !o.ignores_connected_collisions() {
+            continue;
+        }
+        for (_, s) in o.enabled_springs() {
+            let (a, b) = (s.get_particle_a_id(), s.get_particle_b_id());
+            connected.insert((object_id, a.min(b), a.max(b)));
+        }
+    }
+    connected
+}
+
+#[inline]
+fn is_connected(
+    connected: &HashSet<(ObjectKey, ParticleKey, ParticleKey)>,
+    a: &CollisionBody,
+    b: &CollisionBody,
+) -> bool {
+    a.object == b.object
+        && !connected.is_empty()
+        && connected.contains(&(
+            a.object,
+            a.particle.min(b.particle),
+            a.particle.max(b.particle),
+        ))
+}
+
+fn edge_bounds(bodies: &[CollisionBody], e: &CollisionEdge, margin: f32) -> (Vector2D, Vector2D) {
+    let a = &bodies[e.a];
+    let b = &bodies[e.b];
+    let r = edge_radius(bodies, e) + margin;
+    (
+        Vector2D::new(
+            a.position.x.min(b.position.x) - r,
+            a.position.y.min(b.position.y) - r,
+        ),
+        Vector2D::new(
+            a.position.x.max(b.position.x) + r,
+            a.position.y.max(b.position.y) + r,
+        ),
+    )
+}
+
+#[inline]
+fn edge_radius(bodies: &[CollisionBody], e: &CollisionEdge) -> f32 {
+    (bodies[e.a].radius + bodies[e.b].radius) / 2.
+}
+
+/// Push two overlapping particles apart in proportion to their inverse
+/// weights. Returns the normal towards `a`, the penetration and the impulse
+/// if they were touching.
+pub(crate) fn solve_pair(
+    a: &mut CollisionBody,
+    b: &mut CollisionBody,
+) -> Option<(Vector2D, f32, f32)> {
+    let w = a.inv_weight + b.inv_weight;
+    let min_dist = a.radius + b.radius;
+    let delta = b.position - a.position;
+    let dist_sq = delta.magnitude_sq();
+    if w == 0. || dist_sq >= min_dist * min_dist {
+        return None;
+    }
+
+    let dist = dist_sq.sqrt();
+    let n = if dist > 0. {
+        delta / dist
+    } else {
+        Vector2D::new(1., 0.)
+    };
+    let lambda = (min_dist - dist) / w;
+    let correction = n * lambda;
+    a.position -= correction * a.inv_weight;
+    b.position += correction * b.inv_weight;
+    Some((n * -1., min_dist - dist, lambda))
+}
+
+/// Side of the edge the particle was on at the last step, `1` on the side
+/// of the edge's left normal, `-1` on the other and `0` if unclear
+fn edge_side(bodies: &[CollisionBody], p: usize, e: &CollisionEdge) -> f32 {
+    let a = bodies[e.a].last_position;
+    let b = bodies[e.b].last_position;
+    let side = (b - a).cross(bodies[p].last_position - a);
+    if side.abs() <= f32::EPSILON {
+        0.
+    } else {
+        side.signum()
+    }
+}
+
+/// Push a particle out of a spring capsule, moving the spring's end points
+/// according to where along the spring the contact is. Returns the normal
+/// towards the particle, the penetration and the impulse if they were
+/// touching.
+///
+/// Along the middle of the spring the particle is always pushed back to
+/// `side`, so it cannot be dragged through when the spring moves faster
+/// than the particle radius.
+fn solve_particle_edge(
+    bodies: &mut [CollisionBody],
+    p: usize,
+    e: &CollisionEdge,
+    side: f32,
+) -> Option<(Vector2D, f32, f32)> {
+    let pa = bodies[e.a].position;
+    let pb = bodies[e.b].position;
+    let point = bodies[p].position;
+    let min_dist = bodies[p].radius + edge_radius(bodies, e);
+
+    let edge = pb - pa;
+    let len_sq = edge.magnitude_sq();
+    let t = if len_sq > 0. {
+        ((point - pa).dot(edge) / len_sq).clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    let (n, dist) = if side != 0. && t > 0. && t < 1. {
+        let n = edge.perpendicular().normalize() * side;
+        (n, n.dot(point - pa))
+    } else {
+        let delta = point - (pa + edge * t);
+        let dist = delta.magnitude();
+        let n = if dist > 0. {
+            delta / dist
+        } else {
+            edge.perpendicular().normalize()
+        };
+        (n, dist)
+    };
+    if dist >= min_dist {
+        return None;
+    }
+
+    let (wp, wa, wb) = (
+        bodies[p].inv_weight,
+        bodies[e.a].inv_weight,
+        bodies[e.b].inv_weight,
+    );
+    let w = wp + wa * (1. - t) * (1. - t) + wb * t * t;
+    if w == 0. {
+        return None;
+    }
+
+    let lambda = (min_dist - dist) / w;
+    bodies[p].position += n * (lambda * wp);
+    bodies[e.a].position -= n * (lambda * wa * (1. - t));
+    bodies[e.b].position -= n * (lambda * wb * t);
+    Some((n, min_dist - dist, lambda))
+}
+
+/// Push two spring capsules apart at their closest points. Returns the
+/// normal towards `e1`, the penetration and the impulse if they were
+/// touching.
+fn solve_edge_edge(
+    bodies: &mut [CollisionBody],
+    e1: &CollisionEdge,
+    e2: &CollisionEdge,
+) -> Option<(Vector2D, f32, f32)> {
+    let (a1, b1) = (bodies[e1.a].position, bodies[e1.b].position);
+    let (a2, b2) = (bodies[e2.a].position, bodies[e2.b].position);
+    let min_dist = edge_radius(bodies, e1) + edge_radius(bodies, e2);
+
+    let (s, t) = closest_points_between_segments(&a1, &b1, &a2, &b2);
+    let c1 = a1 + (b1 - a1) * s;
+    let c2 = a2 + (b2 - a2) * t;
+    let delta = c1 - c2;
+    let dist_sq = delta.magnitude_sq();
+    if dist_sq >= min_dist * min_dist {
+        return None;
+    }
+
+    let (wa1, wb1) = (bodies[e1.a].inv_weight, bodies[e1.b].inv_weight);
+    let (wa2, wb2) = (bodies[e2.a].inv_weight, bodies[e2.b].inv_weight);
+    let w = wa1 * (1. - s) * (1. - s) + wb1 * s * s + wa2 * (1. - t) * (1. - t) + wb2 * t * t;
+    if w == 0. {
+        return None;
+    }
+
+    let dist = dist_sq.sqrt();
+    let n = if dist > 0. {
+        delta / dist
+    } else {
+        (b2 - a2).perpendicular().normalize()
+    };
+    let lambda = (min_dist - dist) / w;
+    bodies[e1.a].position += n * (lambda * wa1 * (1. - s));
+    bodies[e1.b].position += n * (lambda * wb1 * s);
+    bodies[e2.a].position -= n * (lambda * wa2 * (1. - t));
+    bodies[e2.b].position -= n * (lambda * wb2 * t);
+    Some((n, min_dist - dist, lambda))
+}
+
+/// A particle touching a static collider during a step
+pub(crate) struct ColliderContact {
+    pub object: ObjectKey,
+    pub particle: ParticleKey,
+    pub collider: ColliderKey,
+    pub normal: Vector2D,
+    pub depth: f32,
+    pub impulse: f32,
+}
+
+/// Contacts between particles and colliders found while the solver
+/// iterates, kept once per particle and collider with the deepest
+/// penetration
+#[derive(Default)]
+pub(crate) struct ColliderContacts {
+    contacts: Vec<ColliderContact>,
+    index: HashMap<(ObjectKey, ParticleKey, ColliderKey), usize>,
+}
+
+impl ColliderContacts {
+    pub fn clear(&mut self) {
+        self.contacts.clear();
+        self.index.clear();
+    }
+
+    pub fn record(
+        &mut self,
+        object: ObjectKey,
+        particle: ParticleKey,
+        collider: ColliderKey,
+        normal: Vector2D,
+        depth: f32,
+        impulse: f32,
+    ) {
+        match self.index.get(&(object, particle, collider)) {
+            Some(&i) => {
+                let c = &mut self.contacts[i];
+                c.normal = normal;
+                c.depth = c.depth.max(depth);
+                c.impulse += impulse;
+            }
+            None => {
+                self.index
+                    .insert((object, particle, collider), self.contacts.len());
+                self.contacts.push(ColliderContact {
+                    object,
+                    particle,
+                    collider,
+                    normal,
+                    depth,
+                    impulse,
+                });
+            }
+        }
+    }
+
+    pub fn collect_contacts(&self, out: &mut Vec<Contact>) {
+        out.extend(self.contacts.iter().map(|c| Contact {
+            object: c.object,
+            particle: c.particle,
+            other: ContactTarget2D::Collider(c.collider),
+            normal: c.normal,
+            depth: c.depth,
+            impulse: c.impulse,
+        }));
+    }
+
+    /// Apply friction and restitution to the velocity of every contact,
+    /// once per step after positions have been solved
+    pub fn apply_materials(
+        &mut self,
+        objects: &mut SlotMap<ObjectKey, VerletObject2D>,
+        colliders: &SlotMap<ColliderKey, Collider2D>,
+    ) {
+        for c in self.contacts.iter_mut() {
+            let (o, collider) = match (objects.get_mut(c.object), colliders.get(c.collider)) {
+                (Some(o), Some(collider)) => (o, collider),
+                _ => continue,
+            };
+            let (static_friction, kinetic_friction, restitution) =
+                o.get_material().combine(collider.get_material());
+            if !o.has_particle(c.particle) {
+                continue;
+            }
+            let p = o.get_particle_mut(c.particle);
+
+            let velocity = p.position - p.last_position;
+            let vn = velocity.dot(c.normal);
+            let mut tangent = velocity - c.normal * vn;
+
+            // how hard the particle was pressed against the surface
+            let pressure = c.depth;
+            let slide = tangent.magnitude();
+            if slide <= static_friction * pressure {
+                tangent = Vector2D::zero();
+            } else if slide > 0. {
+                tangent = tangent * (1. - kinetic_friction * pressure / slide).max(0.);
+            }
+
+            // bounce with the speed the particle approached the surface at,
+            // before it was pushed out
+            let approach = c.depth - vn;
+            let normal = if approach > 0. {
+                c.normal * (approach * restitution)
+            } else {
+                c.normal * vn
+            };
+
+            let new_velocity = tangent + normal;
+            p.last_position = p.position - new_velocity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use super::ParticleCollisions;
+    use crate::behaviors::ConstantForceBehavior2D;
+    use crate::colliders::{Collider2D, CollisionFilter2D, Material2D, Shape2D};
+    use crate::{
+        ObjectKey, ParticleKey, SpatialHash2D, Spring2D, Vector2D, VerletObject2D, VerletPhysics2D,
+    };
+
+    fn ball(world: &mut VerletPhysics2D, x: f32, y: f32, radius: f32) -> (ObjectKey, ParticleKey) {
+        let mut object = VerletObject2D::new("ball");
+        let p = object.create_particle(&Vector2D::new(x, y));
+        object.get_particle_mut(p).set_radius(radius);
+        object.set_collisions(true);
+        (world.add_verlet_object(object), p)
+    }
+
+    fn distance(
+        world: &VerletPhysics2D,
+        a: (ObjectKey, ParticleKey),
+        b: (ObjectKey, ParticleKey),
+    ) -> f32 {
+        let pa = *world
+            .get_verlet_object(a.0)
+            .get_particle(a.1)
+            .get_position();
+        let pb = *world
+            .get_verlet_object(b.0)
+            .get_particle(b.1)
+            .get_position();
+        (pb - pa).magnitude()
+    }
+
+    #[test]
+    fn test_particles_push_apart_by_their_radii() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let a = ball(&mut world, 0., 0., 1.);
+        let b = ball(&mut world, 1., 0., 2.);
+        world.update();
+        assert!((distance(&world, a, b) - 3.).abs() < 1e-4);
+
+        // collisions are opt-in on both sides
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let a = ball(&mut world, 0., 0., 1.);
+        let b = ball(&mut world, 1., 0., 2.);
+        world.get_verlet_object_mut(b.0).set_collisions(false);
+        world.update();
+        assert_eq!(distance(&world, a, b), 1.);
+    }
+
+    #[test]
+    fn test_self_collisions_within_object() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(1.);
+        let mut object = VerletObject2D::new("pile");
+        let a = object.create_particle(&Vector2D::new(0., 0.));
+        let b = object.create_particle(&Vector2D::new(0.5, 0.));
+        object.set_self_collisions(true);
+        let id = world.add_verlet_object(object);
+        world.update();
+        assert!((distance(&world, (id, a), (id, b)) - 2.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ball_rests_on_spring_edge() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(0.5);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.05));
+        let mut bridge = VerletObject2D::new("bridge");
+        let left = bridge.create_particle(&Vector2D::new(-5., 0.));
+        let right = bridge.create_particle(&Vector2D::new(5., 0.));
+        bridge.get_particle_mut(left).set_weight(0.);
+        bridge.get_particle_mut(right).set_weight(0.);
+        bridge.add_spring(Spring2D::new(&left, &right, 0.5, None));
+        bridge.set_collisions(true);
+        bridge.set_edge_collisions(true);
+        world.add_verlet_object(bridge);
+        let ball = ball(&mut world, 1., -3., 1.);
+
+        for _ in 0..200 {
+            world.update();
+        }
+        let p = world.get_verlet_object(ball.0).get_particle(ball.1);
+        // the ball sits on the capsule, its radius plus the edge radius above
+        assert!((p.get_position().y + 1.5).abs() < 1e-2);
+        assert!((p.get_position().x - 1.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ignore_connected_collisions() {
+        for ignore in [false, true].iter() {
+            let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+            world.set_particle_radius(1.);
+            let mut rope = VerletObject2D::new("rope");
+            let a = rope.create_particle(&Vector2D::new(0., 0.));
+            let b = rope.create_particle(&Vector2D::new(0.5, 0.));
+            let c = rope.create_particle(&Vector2D::new(0., 0.5));
+            rope.add_spring(Spring2D::new(&a, &b, 0.5, None));
+            rope.set_self_collisions(true);
+            rope.set_ignore_connected_collisions(*ignore);
+            let id = world.add_verlet_object(rope);
+            world.update();
+
+            // unconnected particles always collide
+            assert!(distance(&world, (id, a), (id, c)) > 1.5);
+            let linked = distance(&world, (id, a), (id, b));
+            if *ignore {
+                assert!((linked - 0.5).abs() < 1e-4);
+            } else {
+                assert!(linked > 1.);
+            }
+        }
+    }
+
+    #[test]
+    fn test_layers_and_masks() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let a = ball(&mut world, 0., 0., 1.);
+        let b = ball(&mut world, 1., 0., 1.);
+        let c = ball(&mut world, 0., 1., 1.);
+        world
+            .get_verlet_object_mut(a.0)
+            .set_collision_filter(CollisionFilter2D::new(1, 2));
+        world
+            .get_verlet_object_mut(b.0)
+            .set_collision_filter(CollisionFilter2D::new(2, 1));
+        world
+            .get_verlet_object_mut(c.0)
+            .set_collision_filter(CollisionFilter2D::new(4, u32::MAX));
+        world.update();
+        assert!((distance(&world, a, b) - 2.).abs() < 1e-4);
+        let c_position = *world
+            .get_verlet_object(c.0)
+            .get_particle(c.1)
+            .get_position();
+        assert_eq!(c_position, Vector2D::new(0., 1.));
+
+        // a particle filter overrides the one of its object
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let a = ball(&mut world, 0., 0., 1.);
+        let c = ball(&mut world, 0., 1., 1.);
+        world
+            .get_verlet_object_mut(a.0)
+            .set_collision_filter(CollisionFilter2D::new(1, 2));
+        world
+            .get_verlet_object_mut(c.0)
+            .set_collision_filter(CollisionFilter2D::new(4, u32::MAX));
+        world
+            .get_verlet_object_mut(c.0)
+            .get_particle_mut(c.1)
+            .set_collision_filter(Some(CollisionFilter2D::new(2, 1)));
+        world.update();
+        assert!((distance(&world, a, c) - 2.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_collider_filter() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.1));
+        let a = ball(&mut world, 0., 0., 0.5);
+        let b = ball(&mut world, 10., 0., 0.5);
+        world
+            .get_verlet_object_mut(b.0)
+            .set_collision_filter(CollisionFilter2D::new(2, u32::MAX));
+        let mut floor = Collider2D::new(Shape2D::segment(
+            &Vector2D::new(-20., 1.),
+            &Vector2D::new(20., 1.),
+        ));
+        floor.set_collision_filter(CollisionFilter2D::new(1, 1));
+        world.add_collider(floor);
+
+        for _ in 0..20 {
+            world.update();
+        }
+        let y = |p: (ObjectKey, ParticleKey)| {
+            world
+                .get_verlet_object(p.0)
+                .get_particle(p.1)
+                .get_position()
+                .y
+        };
+        assert!((y(a) - 0.5).abs() < 1e-4);
+        assert!(y(b) > 1.);
+    }
+
+    fn ball_on_floor(material: Material2D, height: f32, speed: f32) -> VerletPhysics2D {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.1));
+        let mut floor = Collider2D::new(Shape2D::segment(
+            &Vector2D::new(-100., 1.),
+            &Vector2D::new(100., 1.),
+        ));
+        floor.set_material(material);
+        world.add_collider(floor);
+        let (id, p) = ball(&mut world, 0., height, 0.5);
+        let object = world.get_verlet_object_mut(id);
+        object.set_material(material);
+        object
+            .get_particle_mut(p)
+            .set_last_position(&Vector2D::new(-speed, height));
+        world
+    }
+
+    fn ball_position(world: &VerletPhysics2D) -> Vector2D {
+        let object = world.get_verlet_objects()[0];
+        *object.get_particles()[0].get_position()
+    }
+
+    #[test]
+    fn test_friction_stops_sliding_ball() {
+        let mut slippery = ball_on_floor(Material2D::new(0., 0., 0.), 0.5, 0.2);
+        let mut rough = ball_on_floor(Material2D::new(10., 10., 0.), 0.5, 0.2);
+        for _ in 0..20 {
+            slippery.update();
+            rough.update();
+        }
+        assert!((ball_position(&slippery).x - 4.).abs() < 1e-3);
+        assert!(ball_position(&rough).x < 0.5);
+    }
+
+    #[test]
+    fn test_restitution_bounces_ball() {
+        let mut bouncy = ball_on_floor(Material2D::new(0., 0., 1.), -2., 0.);
+        let mut dead = ball_on_floor(Material2D::new(0., 0., 0.), -2., 0.);
+        // the first bounce lands at the 7th update
+        let mut highest_bounce = f32::MAX;
+        for i in 0..15 {
+            bouncy.update();
+            dead.update();
+            if i > 6 {
+                highest_bounce = highest_bounce.min(ball_position(&bouncy).y);
+            }
+        }
+        assert!(highest_bounce < -1.);
+        assert!((ball_position(&dead).y - 0.5).abs() < 1e-3);
+    }
+
+    /// A ball moving 10 units per step towards a wall at `y = 0`, returns
+    /// where it is after one step
+    fn fast_ball(wall: impl Fn(&mut VerletPhysics2D), continuous: bool) -> f32 {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_continuous_collisions(continuous);
+        wall(&mut world);
+        let (id, p) = ball(&mut world, 0., -5., 0.1);
+        world
+            .get_verlet_object_mut(id)
+            .get_particle_mut(p)
+            .set_last_position(&Vector2D::new(0., -15.));
+        world.update();
+        world.get_verlet_object(id).get_particle(p).get_position().y
+    }
+
+    #[test]
+    fn test_continuous_collisions_stop_tunneling_through_collider() {
+        let wall = |world: &mut VerletPhysics2D| {
+            world.add_collider(Collider2D::new(Shape2D::segment(
+                &Vector2D::new(-10., 0.),
+                &Vector2D::new(10., 0.),
+            )));
+        };
+        assert!(fast_ball(wall, false) > 4.);
+        assert!((fast_ball(wall, true) + 0.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_continuous_collisions_stop_tunneling_through_spring_edge() {
+        let wall = |world: &mut VerletPhysics2D| {
+            let mut bridge = VerletObject2D::new("bridge");
+            let left = bridge.create_particle(&Vector2D::new(-10., 0.));
+            let right = bridge.create_particle(&Vector2D::new(10., 0.));
+            for &p in [left, right].iter() {
+                let p = bridge.get_particle_mut(p);
+                p.set_weight(0.);
+                p.set_radius(0.1);
+            }
+            bridge.add_spring(Spring2D::new(&left, &right, 0.5, None));
+            bridge.set_collisions(true);
+            bridge.set_edge_collisions(true);
+            world.add_verlet_object(bridge);
+        };
+        assert!(fast_ball(wall, false) > 4.);
+        assert!((fast_ball(wall, true) + 0.2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sweep_edges_stops_particle_crossing_edge() {
+        let mut objects = SlotMap::with_key();
+        let mut bridge = VerletObject2D::new("bridge");
+        let left = bridge.create_particle(&Vector2D::new(-10., 0.));
+        let right = bridge.create_particle(&Vector2D::new(10., 0.));
+        for &p in [left, right].iter() {
+            let p = bridge.get_particle_mut(p);
+            p.set_weight(0.);
+            p.set_radius(0.1);
+        }
+        bridge.add_spring(Spring2D::new(&left, &right, 0.5, None));
+        bridge.set_collisions(true);
+        bridge.set_edge_collisions(true);
+        objects.insert(bridge);
+        let mut ball = VerletObject2D::new("ball");
+        let p = ball.create_particle(&Vector2D::new(0., 5.));
+        ball.get_particle_mut(p).set_radius(0.1);
+        ball.get_particle_mut(p)
+            .set_last_position(&Vector2D::new(0., -5.));
+        ball.set_collisions(true);
+        let ball = objects.insert(ball);
+
+        let mut broadphase = SpatialHash2D::new(1.);
+        for (object_id, o) in objects.iter() {
+            for p in o.get_particles() {
+                broadphase.insert((object_id, p.get_id()), p.get_position(), 0.1);
+            }
+        }
+        let mut collisions = ParticleCollisions::default();
+        collisions.prepare(&objects, &broadphase, 0., true);
+        collisions.sweep_edges(&mut objects);
+        // stopped on the side it came from, clear of the edge's radius
+        let position = objects[ball].get_particle(p).get_position();
+        assert!((position.y + 0.2).abs() < 1e-4);
+    }
+}
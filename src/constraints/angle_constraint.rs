@@ -0,0 +1,130 @@
+use super::ParticleConstraint2D;
+use crate::{Particle2D, ParticleKey};
+
+use std::f32::consts::PI;
+
+/// Keeps the angle at `vertex` between the particles `a` and `c` at its
+/// initial value.
+///
+/// `stiffness` (0 to 1) is the fraction of the angle error corrected on each
+/// application, so a chain of angle constraints behaves like a bendable rod.
+pub struct AngleConstraint2D {
+    particle_ids: [ParticleKey; 3],
+    rest_angle: f32,
+    stiffness: f32,
+}
+
+impl AngleConstraint2D {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        a: &ParticleKey,
+        vertex: &ParticleKey,
+        c: &ParticleKey,
+        stiffness: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(AngleConstraint2D {
+            particle_ids: [*a, *vertex, *c],
+            rest_angle: 0.,
+            stiffness: stiffness.clamp(0., 1.),
+        })
+    }
+
+    pub fn get_rest_angle(&self) -> f32 {
+        self.rest_angle
+    }
+
+    /// Angle to keep, in radians from `a` to `c` around the vertex
+    pub fn set_rest_angle(&mut self, rest_angle: f32) {
+        self.rest_angle = rest_angle;
+    }
+
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.stiffness = stiffness.clamp(0., 1.);
+    }
+
+    fn angle(a: &Particle2D, vertex: &Particle2D, c: &Particle2D) -> f32 {
+        let ba = a.position - vertex.position;
+        let bc = c.position - vertex.position;
+        ba.cross(bc).atan2(ba.dot(bc))
+    }
+}
+
+impl ParticleConstraint2D for AngleConstraint2D {
+    fn init_internal(&mut self, particles: &[&Particle2D]) {
+        self.rest_angle = AngleConstraint2D::angle(particles[0], particles[1], particles[2]);
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let mut diff =
+            self.rest_angle - AngleConstraint2D::angle(&particles[0], &particles[1], &particles[2]);
+        if diff > PI {
+            diff -= 2. * PI;
+        } else if diff < -PI {
+            diff += 2. * PI;
+        }
+
+        let w_a = particles[0].inv_weight;
+        let w_c = particles[2].inv_weight;
+        if w_a + w_c == 0. {
+            return;
+        }
+        let correction = diff * self.stiffness / (w_a + w_c);
+
+        let vertex = particles[1].position;
+        let ba = particles[0].position - vertex;
+        let bc = particles[2].position - vertex;
+        particles[0].position = vertex + ba.rotate(-correction * w_a);
+        particles[2].position = vertex + bc.rotate(correction * w_c);
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        &self.particle_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleKey, Vector2D, VerletObject2D};
+
+    fn bent_angle(obj: &VerletObject2D, ids: &[ParticleKey; 3]) -> f32 {
+        AngleConstraint2D::angle(
+            obj.get_particle(ids[0]),
+            obj.get_particle(ids[1]),
+            obj.get_particle(ids[2]),
+        )
+    }
+
+    #[test]
+    fn test_bent_chain_returns_to_rest_angle() {
+        // a right angle, and a straight chain whose angle sits at the ±π wrap
+        for &(a, c) in [((1., 0.), (0., 1.)), ((-1., 0.), (1., 0.))].iter() {
+            let mut obj = VerletObject2D::new("chain");
+            let ids = [
+                obj.create_particle(&Vector2D::new(a.0, a.1)),
+                obj.create_particle(&Vector2D::new(0., 0.)),
+                obj.create_particle(&Vector2D::new(c.0, c.1)),
+            ];
+            obj.add_constraint(AngleConstraint2D::new(&ids[0], &ids[1], &ids[2], 0.5));
+            let rest = bent_angle(&obj, &ids);
+
+            // bend the chain by 90 degrees
+            let bent = Vector2D::new(c.0, c.1).rotate(PI / 2.);
+            obj.get_particle_mut(ids[2]).set_position(&bent);
+            let mut error = PI / 2.;
+            for _ in 0..20 {
+                obj.apply_constraints();
+                let mut diff = (bent_angle(&obj, &ids) - rest).abs();
+                if diff > PI {
+                    diff = 2. * PI - diff;
+                }
+                assert!(diff < error);
+                error = diff;
+            }
+            assert!(error < 1e-3);
+            let c = obj.get_particle(ids[2]).get_position();
+            assert!((c.magnitude() - 1.).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{friction_once, slide_along, ParticleConstraint2D};
+use crate::{Particle2D, ParticleKey, Vector2D};
+
+/// Keeps a particle inside an axis-aligned box.
+///
+/// The particle moves freely inside the box. When it hits a wall, velocity
+/// into the wall is removed and `friction` (0 to 1) is the fraction of the
+/// motion along it removed each update.
+pub struct BoxConstraint2D {
+    min: Vector2D,
+    max: Vector2D,
+    friction: f32,
+    particle_id: ParticleKey,
+    pending: bool,
+}
+
+impl BoxConstraint2D {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        particle_id: &ParticleKey,
+        min: &Vector2D,
+        max: &Vector2D,
+        friction: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(BoxConstraint2D {
+            min: Vector2D::new(min.x.min(max.x), min.y.min(max.y)),
+            max: Vector2D::new(min.x.max(max.x), min.y.max(max.y)),
+            friction: friction.clamp(0., 1.),
+            particle_id: *particle_id,
+            pending: true,
+        })
+    }
+
+    pub fn set_bounds(&mut self, min: &Vector2D, max: &Vector2D) {
+        self.min = Vector2D::new(min.x.min(max.x), min.y.min(max.y));
+        self.max = Vector2D::new(min.x.max(max.x), min.y.max(max.y));
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.clamp(0., 1.);
+    }
+}
+
+impl ParticleConstraint2D for BoxConstraint2D {
+    fn init_internal(&mut self, _particles: &[&Particle2D]) {}
+
+    fn step(&mut self, _timestep: f32) {
+        self.pending = true;
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let p = &mut particles[0];
+        let x = p.position.x.clamp(self.min.x, self.max.x);
+        let y = p.position.y.clamp(self.min.y, self.max.y);
+
+        if x == p.position.x && y == p.position.y {
+            return;
+        }
+
+        // one wall or a corner, friction applies once either way
+        let friction = friction_once(&mut self.pending, self.friction);
+        if x != p.position.x {
+            p.position.x = x;
+            slide_along(p, Vector2D::new(0., 1.), friction);
+        }
+        if y != p.position.y {
+            p.position.y = y;
+            slide_along(p, Vector2D::new(1., 0.), friction);
+        }
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        std::slice::from_ref(&self.particle_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VerletObject2D, VerletPhysics2D};
+
+    #[test]
+    fn test_particle_stays_inside_box() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        let mut object = VerletObject2D::new("ball");
+        let ball = object.create_particle(&Vector2D::new(1., 1.));
+        object
+            .get_particle_mut(ball)
+            .set_last_position(&Vector2D::new(0.3, 0.4));
+        let (min, max) = (Vector2D::new(0., 0.), Vector2D::new(4., 2.));
+        object.add_constraint(BoxConstraint2D::new(&ball, &min, &max, 0.));
+        let id = world.add_verlet_object(object);
+
+        for _ in 0..50 {
+            world.update();
+            let p = world
+                .get_verlet_object(id)
+                .get_particle(ball)
+                .get_position();
+            assert!(p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y);
+        }
+        // it slid along the bottom wall into the corner
+        let p = world
+            .get_verlet_object(id)
+            .get_particle(ball)
+            .get_position();
+        assert_eq!(*p, max);
+    }
+
+    #[test]
+    fn test_wall_friction_applies_once_per_update() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        let mut object = VerletObject2D::new("ball");
+        let ball = object.create_particle(&Vector2D::new(1., 2.));
+        object
+            .get_particle_mut(ball)
+            .set_last_position(&Vector2D::new(0., 1.5));
+        let (min, max) = (Vector2D::new(0., 0.), Vector2D::new(10., 2.));
+        object.add_constraint(BoxConstraint2D::new(&ball, &min, &max, 0.5));
+        let id = world.add_verlet_object(object);
+
+        world.update();
+        let p = world.get_verlet_object(id).get_particle(ball);
+        let velocity = *p.get_position() - *p.get_last_position();
+        assert!((velocity - Vector2D::new(0.5, 0.)).magnitude() < 1e-6);
+    }
+}
@@ -0,0 +1,98 @@
+use super::{friction_once, slide_along, ParticleConstraint2D};
+use crate::{Particle2D, ParticleKey, Vector2D};
+
+/// Keeps a particle on the rim of a circle, like a bead on a ring.
+///
+/// Radial velocity is removed, and `friction` (0 to 1) is the fraction of
+/// the motion around the circle removed each update.
+pub struct CircleConstraint2D {
+    center: Vector2D,
+    radius: f32,
+    friction: f32,
+    particle_id: ParticleKey,
+    pending: bool,
+}
+
+impl CircleConstraint2D {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        particle_id: &ParticleKey,
+        center: &Vector2D,
+        radius: f32,
+        friction: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(CircleConstraint2D {
+            center: *center,
+            radius,
+            friction: friction.clamp(0., 1.),
+            particle_id: *particle_id,
+            pending: true,
+        })
+    }
+
+    pub fn set_center(&mut self, center: &Vector2D) {
+        self.center = *center;
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.clamp(0., 1.);
+    }
+}
+
+impl ParticleConstraint2D for CircleConstraint2D {
+    fn init_internal(&mut self, _particles: &[&Particle2D]) {}
+
+    fn step(&mut self, _timestep: f32) {
+        self.pending = true;
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let p = &mut particles[0];
+        let mut radial = (p.position - self.center).normalize();
+        if radial.magnitude_sq() == 0. {
+            radial = Vector2D::new(1., 0.);
+        }
+        p.position = self.center + radial * self.radius;
+        let friction = friction_once(&mut self.pending, self.friction);
+        slide_along(p, radial.perpendicular(), friction);
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        std::slice::from_ref(&self.particle_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::ConstantForceBehavior2D;
+    use crate::{VerletObject2D, VerletPhysics2D};
+
+    #[test]
+    fn test_bead_stays_on_ring() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.1));
+        let mut object = VerletObject2D::new("bead");
+        let bead = object.create_particle(&Vector2D::new(5., 0.));
+        object.add_constraint(CircleConstraint2D::new(&bead, &Vector2D::zero(), 5., 0.));
+        let id = world.add_verlet_object(object);
+
+        let mut lowest: f32 = 0.;
+        for _ in 0..100 {
+            world.update();
+            let p = world
+                .get_verlet_object(id)
+                .get_particle(bead)
+                .get_position();
+            assert!((p.magnitude() - 5.).abs() < 1e-4);
+            lowest = lowest.max(p.y);
+        }
+        // it swung down through the bottom of the ring
+        assert!(lowest > 4.9);
+    }
+}
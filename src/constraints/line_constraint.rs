@@ -0,0 +1,153 @@
+use super::{friction_once, slide_along, ParticleConstraint2D};
+use crate::{Particle2D, ParticleKey, Vector2D};
+
+/// Keeps a particle on a line segment, or on the infinite line through two
+/// points.
+///
+/// Velocity across the line is removed, and `friction` (0 to 1) is the
+/// fraction of the motion along it removed each update.
+pub struct LineConstraint2D {
+    start: Vector2D,
+    end: Vector2D,
+    infinite: bool,
+    friction: f32,
+    particle_id: ParticleKey,
+    pending: bool,
+}
+
+impl LineConstraint2D {
+    /// Constrain to the segment between `start` and `end`
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        particle_id: &ParticleKey,
+        start: &Vector2D,
+        end: &Vector2D,
+        friction: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        LineConstraint2D::new_with(particle_id, start, end, false, friction)
+    }
+
+    /// Constrain to the infinite line passing through `start` and `end`
+    pub fn infinite(
+        particle_id: &ParticleKey,
+        start: &Vector2D,
+        end: &Vector2D,
+        friction: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        LineConstraint2D::new_with(particle_id, start, end, true, friction)
+    }
+
+    fn new_with(
+        particle_id: &ParticleKey,
+        start: &Vector2D,
+        end: &Vector2D,
+        infinite: bool,
+        friction: f32,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(LineConstraint2D {
+            start: *start,
+            end: *end,
+            infinite,
+            friction: friction.clamp(0., 1.),
+            particle_id: *particle_id,
+            pending: true,
+        })
+    }
+
+    pub fn set_points(&mut self, start: &Vector2D, end: &Vector2D) {
+        self.start = *start;
+        self.end = *end;
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.clamp(0., 1.);
+    }
+}
+
+impl ParticleConstraint2D for LineConstraint2D {
+    fn init_internal(&mut self, _particles: &[&Particle2D]) {}
+
+    fn step(&mut self, _timestep: f32) {
+        self.pending = true;
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let p = &mut particles[0];
+        let dir = self.end - self.start;
+        let len_sq = dir.magnitude_sq();
+        if len_sq == 0. {
+            p.position = self.start;
+            p.last_position = self.start;
+            return;
+        }
+
+        let mut t = (p.position - self.start).dot(dir) / len_sq;
+        if !self.infinite {
+            t = t.clamp(0., 1.);
+        }
+        p.position = self.start + dir * t;
+        let friction = friction_once(&mut self.pending, self.friction);
+        slide_along(p, dir.normalize(), friction);
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        std::slice::from_ref(&self.particle_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::ConstantForceBehavior2D;
+    use crate::{VerletObject2D, VerletPhysics2D};
+
+    #[test]
+    fn test_bead_slides_along_segment() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.1));
+        let mut object = VerletObject2D::new("bead");
+        let free = object.create_particle(&Vector2D::new(0., 0.));
+        let stuck = object.create_particle(&Vector2D::new(0., 0.));
+        let (start, end) = (Vector2D::new(0., 0.), Vector2D::new(10., 10.));
+        object.add_constraint(LineConstraint2D::new(&free, &start, &end, 0.));
+        object.add_constraint(LineConstraint2D::new(&stuck, &start, &end, 1.));
+        let id = world.add_verlet_object(object);
+
+        for _ in 0..100 {
+            world.update();
+            let p = world
+                .get_verlet_object(id)
+                .get_particle(free)
+                .get_position();
+            assert!((p.x - p.y).abs() < 1e-4);
+            assert!(p.x >= 0. && p.x <= 10.);
+        }
+        let object = world.get_verlet_object(id);
+        assert_eq!(*object.get_particle(free).get_position(), end);
+        // full friction removes all velocity along the line every update
+        let stuck = object.get_particle(stuck);
+        assert_eq!(*stuck.get_position(), *stuck.get_last_position());
+        assert!(stuck.get_position().x < 10.);
+    }
+
+    #[test]
+    fn test_partial_friction_applies_once_per_update() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        let mut object = VerletObject2D::new("bead");
+        let bead = object.create_particle(&Vector2D::new(0., 0.));
+        object
+            .get_particle_mut(bead)
+            .set_last_position(&Vector2D::new(-1., 0.));
+        let (start, end) = (Vector2D::new(-10., 0.), Vector2D::new(10., 0.));
+        object.add_constraint(LineConstraint2D::new(&bead, &start, &end, 0.5));
+        let id = world.add_verlet_object(object);
+
+        for expected in [0.5, 0.25].iter() {
+            world.update();
+            let p = world.get_verlet_object(id).get_particle(bead);
+            let velocity = *p.get_position() - *p.get_last_position();
+            assert!((velocity.x - expected).abs() < 1e-6);
+        }
+    }
+}
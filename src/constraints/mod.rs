@@ -1,13 +1,86 @@
+mod angle_constraint;
+mod box_constraint;
+mod circle_constraint;
+mod line_constraint;
 mod pin_constraint;
+mod pulley_constraint;
+mod soft_pin_constraint;
 
-use crate::{Particle2D, ParticleKey};
+use slotmap::new_key_type;
+use std::any::Any;
 
-pub trait ParticleConstraint2D {
-    fn init_internal(&mut self, particle: &Particle2D);
+use crate::{Particle2D, ParticleKey, Vector2D};
 
-    fn get_particle_id(&self) -> ParticleKey;
+new_key_type! { pub struct ConstraintKey; }
 
-    fn apply(&mut self, particle: &mut Particle2D);
+/// A constraint over one or more particles of a
+/// [`VerletObject2D`](crate::VerletObject2D).
+///
+/// `get_particle_ids` declares the particles the constraint acts on, these
+/// must be distinct. `init_internal` and `apply` receive the particles in the
+/// same order.
+///
+/// `apply` works on copies of the particles, which is what lets it change
+/// any number of them at once. After it returns every copy is written back
+/// to the object, replacing the particle with the same key, so changes to
+/// the copies are kept and nothing else should be changed during `apply`.
+/// It is called more than once per engine update, while `step` is called
+/// exactly once.
+pub trait ParticleConstraint2D: AsAny2D {
+    fn init_internal(&mut self, particles: &[&Particle2D]);
+
+    fn get_particle_ids(&self) -> &[ParticleKey];
+
+    /// Advance any time dependent state, called once per engine update
+    fn step(&mut self, _timestep: f32) {}
+
+    fn apply(&mut self, particles: &mut [Particle2D]);
+}
+
+/// Gives access to the concrete type behind a `dyn`
+/// [`ParticleConstraint2D`], implemented for every constraint type, see
+/// [`VerletObject2D::get_constraint_as_mut`](crate::VerletObject2D::get_constraint_as_mut)
+pub trait AsAny2D {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny2D for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// `friction` on the first call after `pending` was set by `step` and no
+/// friction after that, so friction is applied once per update even though
+/// constraints are applied more than once
+#[inline]
+pub(crate) fn friction_once(pending: &mut bool, friction: f32) -> f32 {
+    if std::mem::replace(pending, false) {
+        friction
+    } else {
+        0.
+    }
+}
+
+/// Keep only the part of the particle's velocity along `tangent` (a unit
+/// vector), scaled down by `friction`
+#[inline]
+pub(crate) fn slide_along(p: &mut Particle2D, tangent: Vector2D, friction: f32) {
+    let velocity = p.position - p.last_position;
+    let along = tangent * (velocity.dot(tangent) * (1. - friction));
+    p.last_position = p.position - along;
 }
 
+pub use angle_constraint::AngleConstraint2D;
+pub use box_constraint::BoxConstraint2D;
+pub use circle_constraint::CircleConstraint2D;
+pub use line_constraint::LineConstraint2D;
 pub use pin_constraint::PinConstraint2D;
+pub use pulley_constraint::PulleyConstraint2D;
+pub use soft_pin_constraint::SoftPinConstraint2D;
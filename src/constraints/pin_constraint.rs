@@ -1,33 +1,135 @@
 use super::ParticleConstraint2D;
-use crate::{Particle2D, ParticleKey, Vector2D};
+use crate::{Anchor2D, Particle2D, ParticleKey, Path2D, Vector2D};
 
-#[derive(Clone)]
+enum PinTarget {
+    Fixed,
+    Anchor(Anchor2D),
+    Path(Path2D, f32),
+}
+
+/// Holds a particle at a target position.
+///
+/// The target is either fixed at the particle's position when the constraint
+/// is added, follows an [`Anchor2D`], or is tweened along a [`Path2D`]. When
+/// the target moves, the particle inherits its motion as velocity.
 pub struct PinConstraint2D {
     position: Vector2D,
+    last_position: Vector2D,
     particle_id: ParticleKey,
+    target: PinTarget,
 }
 
 impl PinConstraint2D {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(particle_id: &ParticleKey) -> Box<dyn ParticleConstraint2D> {
+        PinConstraint2D::new_with(particle_id, PinTarget::Fixed)
+    }
+
+    /// Pin that follows `anchor`, move the anchor to move the particle
+    pub fn with_anchor(
+        particle_id: &ParticleKey,
+        anchor: &Anchor2D,
+    ) -> Box<dyn ParticleConstraint2D> {
+        PinConstraint2D::new_with(particle_id, PinTarget::Anchor(anchor.clone()))
+    }
+
+    /// Pin that moves along `path`, advancing by the engine timestep each
+    /// update
+    pub fn with_path(particle_id: &ParticleKey, path: Path2D) -> Box<dyn ParticleConstraint2D> {
+        PinConstraint2D::new_with(particle_id, PinTarget::Path(path, 0.))
+    }
+
+    /// Current target position
+    pub fn get_position(&self) -> Vector2D {
+        self.position
+    }
+
+    /// Move a fixed pin, the particle is moved there without gaining
+    /// velocity. Pins following an anchor or a path ignore this.
+    pub fn set_position(&mut self, position: &Vector2D) {
+        if let PinTarget::Fixed = self.target {
+            self.position.set_v(position);
+            self.last_position.set_v(position);
+        }
+    }
+
+    fn new_with(particle_id: &ParticleKey, target: PinTarget) -> Box<dyn ParticleConstraint2D> {
         Box::new(PinConstraint2D {
             position: Vector2D::default(),
-            particle_id: particle_id.clone(),
+            last_position: Vector2D::default(),
+            particle_id: *particle_id,
+            target,
         })
     }
 }
 
 impl ParticleConstraint2D for PinConstraint2D {
-    fn init_internal(&mut self, p: &Particle2D) {
-        self.position.set_v(p.get_position());
+    fn init_internal(&mut self, particles: &[&Particle2D]) {
+        let p = particles[0];
+        match &self.target {
+            PinTarget::Fixed => self.position.set_v(p.get_position()),
+            PinTarget::Anchor(anchor) => self.position = anchor.get_position(),
+            PinTarget::Path(path, time) => self.position = path.sample(*time),
+        }
+        self.last_position.set_v(&self.position);
     }
 
-    fn apply(&mut self, p: &mut Particle2D) {
+    fn step(&mut self, timestep: f32) {
+        self.last_position.set_v(&self.position);
+        match &mut self.target {
+            PinTarget::Fixed => (),
+            PinTarget::Anchor(anchor) => self.position = anchor.get_position(),
+            PinTarget::Path(path, time) => {
+                *time += timestep;
+                self.position = path.sample(*time);
+            }
+        }
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let p = &mut particles[0];
         p.position.set_v(&self.position);
-        p.last_position.set_v(&self.position);
+        p.last_position.set_v(&self.last_position);
     }
 
     #[inline(always)]
-    fn get_particle_id(&self) -> ParticleKey {
-        self.particle_id
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        std::slice::from_ref(&self.particle_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_passes_target_motion_as_velocity() {
+        let mut p = Particle2D::new(ParticleKey::default(), &Vector2D::new(0., 0.));
+
+        let anchor = Anchor2D::new(&Vector2D::new(1., 0.));
+        let mut pin = PinConstraint2D::with_anchor(&p.get_id(), &anchor);
+        pin.init_internal(&[&p]);
+        anchor.set_position(&Vector2D::new(3., 1.));
+        pin.step(1.);
+        pin.apply(std::slice::from_mut(&mut p));
+        assert_eq!(*p.get_position(), Vector2D::new(3., 1.));
+        assert_eq!(
+            *p.get_position() - *p.get_last_position(),
+            Vector2D::new(2., 1.)
+        );
+
+        let path = Path2D::new(
+            vec![(0., Vector2D::new(0., 0.)), (2., Vector2D::new(4., 0.))],
+            false,
+        );
+        let mut pin = PinConstraint2D::with_path(&p.get_id(), path);
+        pin.init_internal(&[&p]);
+        pin.step(1.);
+        pin.apply(std::slice::from_mut(&mut p));
+        assert_eq!(*p.get_position(), Vector2D::new(2., 0.));
+        assert_eq!(
+            *p.get_position() - *p.get_last_position(),
+            Vector2D::new(2., 0.)
+        );
     }
 }
@@ -0,0 +1,216 @@
+use super::ParticleConstraint2D;
+use crate::{Particle2D, ParticleKey, Vector2D};
+
+/// Keeps the total length of rope running from fixed pulley points to
+/// particles constant.
+///
+/// Each segment runs from a particle to a fixed point and has a ratio, the
+/// number of times the rope runs along that segment. A weight hanging over a
+/// pulley is two segments sharing the same point, a block-and-tackle uses
+/// ratios greater than one.
+///
+/// A rope pulley only keeps `Σ ratio * |particle - point|` at most the rope
+/// length, so the rope can go slack but never stretch. A rigid pulley keeps
+/// the sum exactly at the length, pushing particles out as well as pulling
+/// them in.
+pub struct PulleyConstraint2D {
+    particle_ids: Vec<ParticleKey>,
+    points: Vec<Vector2D>,
+    ratios: Vec<f32>,
+    length: f32,
+    rigid: bool,
+}
+
+impl PulleyConstraint2D {
+    /// One-sided rope over `(particle, point)` segments, each with a ratio
+    /// of one. The rope can go slack but never stretch.
+    ///
+    /// When `length` is `None` the total length at the time the constraint
+    /// is added is used.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        segments: &[(ParticleKey, Vector2D)],
+        length: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        PulleyConstraint2D::with_ratios(&with_unit_ratios(segments), length)
+    }
+
+    /// One-sided rope over `(particle, point, ratio)` segments
+    pub fn with_ratios(
+        segments: &[(ParticleKey, Vector2D, f32)],
+        length: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(PulleyConstraint2D::build(segments, length, false))
+    }
+
+    /// Two-sided pulley over `(particle, point)` segments, each with a ratio
+    /// of one. The summed length stays constant, so pushing one particle
+    /// towards its point pushes the others away from theirs.
+    pub fn rigid(
+        segments: &[(ParticleKey, Vector2D)],
+        length: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        PulleyConstraint2D::rigid_with_ratios(&with_unit_ratios(segments), length)
+    }
+
+    /// Two-sided pulley over `(particle, point, ratio)` segments
+    pub fn rigid_with_ratios(
+        segments: &[(ParticleKey, Vector2D, f32)],
+        length: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(PulleyConstraint2D::build(segments, length, true))
+    }
+
+    fn build(
+        segments: &[(ParticleKey, Vector2D, f32)],
+        length: Option<f32>,
+        rigid: bool,
+    ) -> PulleyConstraint2D {
+        PulleyConstraint2D {
+            particle_ids: segments.iter().map(|s| s.0).collect(),
+            points: segments.iter().map(|s| s.1).collect(),
+            ratios: segments.iter().map(|s| s.2).collect(),
+            length: length.unwrap_or(-1.),
+            rigid,
+        }
+    }
+
+    /// Whether the pulley also pushes particles out when the summed length
+    /// falls below the rope length
+    pub fn is_rigid(&self) -> bool {
+        self.rigid
+    }
+
+    pub fn set_rigid(&mut self, rigid: bool) {
+        self.rigid = rigid;
+    }
+
+    /// Length of the rope, `Σ ratio * |particle - point|` can not exceed it,
+    /// and can not fall below it for a rigid pulley
+    pub fn get_length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn set_length(&mut self, length: f32) {
+        self.length = length;
+    }
+
+    fn current_length<'a, I: Iterator<Item = &'a Vector2D>>(&self, positions: I) -> f32 {
+        positions
+            .zip(self.points.iter().zip(self.ratios.iter()))
+            .map(|(p, (point, ratio))| (*p - *point).magnitude() * ratio)
+            .sum()
+    }
+}
+
+fn with_unit_ratios(segments: &[(ParticleKey, Vector2D)]) -> Vec<(ParticleKey, Vector2D, f32)> {
+    segments.iter().map(|(k, p)| (*k, *p, 1.)).collect()
+}
+
+impl ParticleConstraint2D for PulleyConstraint2D {
+    fn init_internal(&mut self, particles: &[&Particle2D]) {
+        if self.length < 0. {
+            self.length = self.current_length(particles.iter().map(|p| p.get_position()));
+        }
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let error = self.current_length(particles.iter().map(|p| p.get_position())) - self.length;
+        if error == 0. || (error < 0. && !self.rigid) {
+            return;
+        }
+
+        let denom: f32 = particles
+            .iter()
+            .zip(self.ratios.iter())
+            .map(|(p, r)| p.inv_weight * r * r)
+            .sum();
+        if denom == 0. {
+            return;
+        }
+
+        let lambda = error / denom;
+        for ((p, point), ratio) in particles
+            .iter_mut()
+            .zip(self.points.iter())
+            .zip(self.ratios.iter())
+        {
+            let n = (p.position - *point).normalize();
+            p.position -= n * (lambda * ratio * p.inv_weight);
+        }
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        &self.particle_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::ConstantForceBehavior2D;
+    use crate::{VerletObject2D, VerletPhysics2D};
+
+    #[test]
+    fn test_pulley_never_exceeds_its_length() {
+        let mut world = VerletPhysics2D::new_with(1.0, 5, 0.0, None);
+        world.add_behavior(ConstantForceBehavior2D::new(0., 0.01));
+        let mut object = VerletObject2D::new("pulley");
+        let light = object.create_particle(&Vector2D::new(-1., 5.));
+        let heavy = object.create_particle(&Vector2D::new(1., 5.));
+        object.get_particle_mut(heavy).set_weight(3.);
+        let wheel = Vector2D::new(0., 0.);
+        object.add_constraint(PulleyConstraint2D::new(
+            &[(light, wheel), (heavy, wheel)],
+            None,
+        ));
+        let id = world.add_verlet_object(object);
+
+        let length = 2. * Vector2D::new(1., 5.).magnitude();
+        for _ in 0..20 {
+            world.update();
+            let object = world.get_verlet_object(id);
+            let total = (*object.get_particle(light).get_position() - wheel).magnitude()
+                + (*object.get_particle(heavy).get_position() - wheel).magnitude();
+            assert!(total <= length + 1e-3);
+        }
+        // the heavy side wins and pulls the light particle up
+        let object = world.get_verlet_object(id);
+        assert!(object.get_particle(light).get_position().y < 5.);
+        assert!(object.get_particle(heavy).get_position().y > 5.);
+    }
+
+    #[test]
+    fn test_rigid_pulley_keeps_its_length() {
+        let wheel = Vector2D::new(0., 0.);
+        for &rigid in [false, true].iter() {
+            let mut object = VerletObject2D::new("pulley");
+            let left = object.create_particle(&Vector2D::new(-1., 4.));
+            let right = object.create_particle(&Vector2D::new(1., 4.));
+            let segments = [(left, wheel), (right, wheel)];
+            object.add_constraint(if rigid {
+                PulleyConstraint2D::rigid(&segments, None)
+            } else {
+                PulleyConstraint2D::new(&segments, None)
+            });
+            let length = 2. * Vector2D::new(1., 4.).magnitude();
+
+            // push the left particle halfway towards the wheel
+            object
+                .get_particle_mut(left)
+                .set_position(&Vector2D::new(-0.5, 2.));
+            object.apply_constraints();
+            let total = (*object.get_particle(left).get_position() - wheel).magnitude()
+                + (*object.get_particle(right).get_position() - wheel).magnitude();
+            if rigid {
+                assert!((total - length).abs() < 1e-4);
+                assert!(object.get_particle(right).get_position().y > 4.);
+            } else {
+                // a rope just goes slack
+                assert!(total < length - 1.);
+                assert_eq!(object.get_particle(right).get_position().y, 4.);
+            }
+        }
+    }
+}
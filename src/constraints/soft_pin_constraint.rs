@@ -0,0 +1,201 @@
+use super::ParticleConstraint2D;
+use crate::{Anchor2D, Particle2D, ParticleKey, Vector2D};
+
+enum Softness {
+    Stiffness(f32),
+    Compliance(f32),
+}
+
+/// Pulls a particle towards an anchor point instead of snapping it there.
+///
+/// Once per engine update the particle is moved `stiffness` of the way to
+/// the anchor, so `1.0` behaves like a hard pin and small values give an
+/// elastic tether. Alternatively a `compliance` (the inverse of a spring
+/// constant) makes heavier particles follow more slowly. The pull moves the
+/// particle without moving its last position, so like a spring it also
+/// changes the particle's velocity. With a `max_distance` the particle is
+/// never allowed further than that from the anchor.
+pub struct SoftPinConstraint2D {
+    anchor: Anchor2D,
+    particle_id: ParticleKey,
+    softness: Softness,
+    max_distance: Option<f32>,
+    timestep: f32,
+    pending: bool,
+}
+
+impl SoftPinConstraint2D {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        particle_id: &ParticleKey,
+        position: &Vector2D,
+        stiffness: f32,
+        max_distance: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        SoftPinConstraint2D::with_anchor(
+            particle_id,
+            &Anchor2D::new(position),
+            stiffness,
+            max_distance,
+        )
+    }
+
+    /// Soft pin that follows `anchor`
+    pub fn with_anchor(
+        particle_id: &ParticleKey,
+        anchor: &Anchor2D,
+        stiffness: f32,
+        max_distance: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        SoftPinConstraint2D::new_with(
+            particle_id,
+            anchor,
+            Softness::Stiffness(stiffness.clamp(0., 1.)),
+            max_distance,
+        )
+    }
+
+    /// Soft pin that follows `anchor` with a `compliance` instead of a
+    /// stiffness, zero compliance behaves like a hard pin
+    pub fn with_compliance(
+        particle_id: &ParticleKey,
+        anchor: &Anchor2D,
+        compliance: f32,
+        max_distance: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        SoftPinConstraint2D::new_with(
+            particle_id,
+            anchor,
+            Softness::Compliance(compliance.max(0.)),
+            max_distance,
+        )
+    }
+
+    fn new_with(
+        particle_id: &ParticleKey,
+        anchor: &Anchor2D,
+        softness: Softness,
+        max_distance: Option<f32>,
+    ) -> Box<dyn ParticleConstraint2D> {
+        Box::new(SoftPinConstraint2D {
+            anchor: anchor.clone(),
+            particle_id: *particle_id,
+            softness,
+            max_distance,
+            timestep: 1.0,
+            pending: true,
+        })
+    }
+
+    /// Anchor the particle is pulled to, move it to move the target
+    pub fn get_anchor(&self) -> &Anchor2D {
+        &self.anchor
+    }
+
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.softness = Softness::Stiffness(stiffness.clamp(0., 1.));
+    }
+
+    pub fn set_compliance(&mut self, compliance: f32) {
+        self.softness = Softness::Compliance(compliance.max(0.));
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: Option<f32>) {
+        self.max_distance = max_distance;
+    }
+
+    pub fn get_max_distance(&self) -> Option<f32> {
+        self.max_distance
+    }
+
+    /// Fraction of the way to the anchor the particle is moved
+    #[inline]
+    fn pull(&self, p: &Particle2D) -> f32 {
+        match self.softness {
+            Softness::Stiffness(stiffness) => stiffness,
+            Softness::Compliance(compliance) => {
+                let w = p.inv_weight;
+                if w == 0. {
+                    return 0.;
+                }
+                w / (w + compliance / (self.timestep * self.timestep))
+            }
+        }
+    }
+}
+
+impl ParticleConstraint2D for SoftPinConstraint2D {
+    fn init_internal(&mut self, _particles: &[&Particle2D]) {}
+
+    fn step(&mut self, timestep: f32) {
+        self.timestep = timestep;
+        self.pending = true;
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let p = &mut particles[0];
+        let anchor = self.anchor.get_position();
+
+        // constraints are applied more than once per update, only pull on
+        // the first application so the stiffness is per update
+        if self.pending {
+            self.pending = false;
+            let delta = anchor - p.position;
+            p.position += delta * self.pull(p);
+        }
+
+        if let Some(max_distance) = self.max_distance {
+            let offset = p.position - anchor;
+            let dist = offset.magnitude();
+            if dist > max_distance && dist > 0. {
+                p.position = anchor + offset * (max_distance / dist);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        std::slice::from_ref(&self.particle_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soft_pin_pulls_once_per_update_within_max_distance() {
+        let mut p = Particle2D::new(ParticleKey::default(), &Vector2D::new(10., 0.));
+        let mut pin = SoftPinConstraint2D::new(&p.get_id(), &Vector2D::new(0., 0.), 0.5, Some(4.));
+
+        pin.step(1.);
+        pin.apply(std::slice::from_mut(&mut p));
+        // pulled half way to 5, then held at the maximum distance
+        assert_eq!(*p.get_position(), Vector2D::new(4., 0.));
+
+        // a second application in the same update does not pull again
+        p.set_position(&Vector2D::new(2., 0.));
+        pin.apply(std::slice::from_mut(&mut p));
+        assert_eq!(*p.get_position(), Vector2D::new(2., 0.));
+
+        pin.step(1.);
+        pin.apply(std::slice::from_mut(&mut p));
+        assert_eq!(*p.get_position(), Vector2D::new(1., 0.));
+    }
+
+    #[test]
+    fn test_soft_pin_compliance_scales_with_weight() {
+        let anchor = Anchor2D::new(&Vector2D::new(0., 0.));
+        let mut light = Particle2D::new(ParticleKey::default(), &Vector2D::new(4., 0.));
+        let mut heavy = light.clone();
+        heavy.set_weight(3.);
+
+        for p in [&mut light, &mut heavy] {
+            let mut pin = SoftPinConstraint2D::with_compliance(&p.get_id(), &anchor, 1., None);
+            pin.step(1.);
+            pin.apply(std::slice::from_mut(p));
+        }
+        assert_eq!(*light.get_position(), Vector2D::new(2., 0.));
+        assert_eq!(*heavy.get_position(), Vector2D::new(3., 0.));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::colliders::{ColliderKey, SensorKey};
+use crate::{ObjectKey, ParticleKey, Vector2D};
+
+/// Whether a contact started this step, continued from the last step or
+/// ended
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContactPhase2D {
+    Begin,
+    Persist,
+    End,
+}
+
+/// What a particle touched
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ContactTarget2D {
+    Collider(ColliderKey),
+    /// Another particle, given by its object and key
+    Particle(ObjectKey, ParticleKey),
+    /// A spring edge, given by its object and the keys of its two particles
+    Spring(ObjectKey, ParticleKey, ParticleKey),
+}
+
+/// A contact between a particle and something else during a step.
+///
+/// The `normal` points from the other side towards the particle. `depth` is
+/// the deepest penetration seen during the step, both are zero for `End`
+/// events.
+///
+/// `impulse` is the momentum exchanged along the normal, in the same unit
+/// for every kind of contact: the distance the solver pushed the particle
+/// out times its weight, summed over the solver iterations. Since velocity
+/// is the motion over one step this is weight times velocity. For a spring
+/// edge it is shared between the edge's two particles, and friction and
+/// restitution applied afterwards are not included.
+///
+/// Two spring edges touching are reported on the particle of each edge
+/// nearest to the contact, with the other edge as `other`.
+#[derive(Clone, Copy, Debug)]
+pub struct ContactEvent2D {
+    pub phase: ContactPhase2D,
+    pub object: ObjectKey,
+    pub tag: &'static str,
+    pub particle: ParticleKey,
+    pub other: ContactTarget2D,
+    pub normal: Vector2D,
+    pub depth: f32,
+    pub impulse: f32,
+}
+
+/// Whether something entered a sensor this step, stayed inside or left it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SensorPhase2D {
+    Enter,
+    Stay,
+    Exit,
+}
+
+/// A particle or object overlapping a sensor.
+///
+/// Each particle gets its own events, with `particle` set. An object gets
+/// events with `particle` set to `None`, it enters with its first particle
+/// and exits with its last one.
+#[derive(Clone, Copy, Debug)]
+pub struct SensorEvent2D {
+    pub phase: SensorPhase2D,
+    pub sensor: SensorKey,
+    pub object: ObjectKey,
+    pub tag: &'static str,
+    pub particle: Option<ParticleKey>,
+}
+
+/// A contact found by the solver, before it is turned into an event
+pub(crate) struct Contact {
+    pub object: ObjectKey,
+    pub particle: ParticleKey,
+    pub other: ContactTarget2D,
+    pub normal: Vector2D,
+    pub depth: f32,
+    pub impulse: f32,
+}
@@ -0,0 +1,279 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::constraints::{ConstraintKey, PinConstraint2D, SoftPinConstraint2D};
+use crate::{
+    Anchor2D, ObjectKey, Particle2D, ParticleConstraint2D, ParticleKey, Vector2D, VerletPhysics2D,
+};
+
+/// How a dragged particle is held to the pointer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DragMode2D {
+    /// The particle follows the pointer exactly
+    Hard,
+    /// The particle is pulled `stiffness` of the way to the pointer each
+    /// update, and optionally kept within `max_distance` of it
+    Soft {
+        stiffness: f32,
+        max_distance: Option<f32>,
+    },
+}
+
+/// Picks up particles with a pointer and drags them around.
+///
+/// Call [`grab`](Dragger2D::grab) when the pointer is pressed,
+/// [`move_to`](Dragger2D::move_to) when it moves, [`update`](Dragger2D::update)
+/// after every engine update and [`release`](Dragger2D::release) when the
+/// pointer is let go. While dragging, the particle is held by a pin or soft
+/// pin constraint added to its object, so the engine solves it together with
+/// everything else.
+pub struct Dragger2D {
+    mode: DragMode2D,
+    grab_radius: f32,
+    break_force: Option<f32>,
+    anchor: Anchor2D,
+    last_pointer: Vector2D,
+    pointer_velocity: Vector2D,
+    target: Option<(ObjectKey, ParticleKey)>,
+    constraint: Option<ConstraintKey>,
+    pull: Rc<Cell<f32>>,
+}
+
+impl Dragger2D {
+    pub fn new(mode: DragMode2D, grab_radius: f32) -> Self {
+        Dragger2D {
+            mode,
+            grab_radius,
+            break_force: None,
+            anchor: Anchor2D::new(&Vector2D::zero()),
+            last_pointer: Vector2D::zero(),
+            pointer_velocity: Vector2D::zero(),
+            target: None,
+            constraint: None,
+            pull: Rc::new(Cell::new(0.)),
+        }
+    }
+
+    /// Used for the next grab
+    pub fn set_mode(&mut self, mode: DragMode2D) {
+        self.mode = mode;
+    }
+
+    pub fn get_mode(&self) -> DragMode2D {
+        self.mode
+    }
+
+    pub fn set_grab_radius(&mut self, radius: f32) {
+        self.grab_radius = radius;
+    }
+
+    pub fn get_grab_radius(&self) -> f32 {
+        self.grab_radius
+    }
+
+    /// Let go of the particle when the force needed to hold it, its weight
+    /// times the acceleration applied by the drag, goes above `force`
+    pub fn set_break_force(&mut self, force: Option<f32>) {
+        self.break_force = force;
+    }
+
+    pub fn get_break_force(&self) -> Option<f32> {
+        self.break_force
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// The particle being dragged
+    pub fn get_target(&self) -> Option<(ObjectKey, ParticleKey)> {
+        self.target
+    }
+
+    /// Pointer movement during the last update
+    pub fn get_pointer_velocity(&self) -> Vector2D {
+        self.pointer_velocity
+    }
+
+    /// The constraint holding the dragged particle, part of the particle's
+    /// object
+    pub fn get_constraint(&self) -> Option<(ObjectKey, ConstraintKey)> {
+        match (self.target, self.constraint) {
+            (Some((object_id, _)), Some(constraint)) => Some((object_id, constraint)),
+            _ => None,
+        }
+    }
+
+    /// Grab the particle nearest to `point` within the grab radius, returns
+    /// whether one was found. Anything dragged before is released.
+    pub fn grab(&mut self, physics: &mut VerletPhysics2D, point: &Vector2D) -> bool {
+        self.release(physics);
+        let (object_id, particle_id) = match physics.nearest_particle(point, self.grab_radius) {
+            Some(found) => found,
+            None => return false,
+        };
+
+        self.anchor.set_position(point);
+        self.last_pointer = *point;
+        self.pointer_velocity = Vector2D::zero();
+
+        let constraint = match self.mode {
+            DragMode2D::Hard => PinConstraint2D::with_anchor(&particle_id, &self.anchor),
+            DragMode2D::Soft {
+                stiffness,
+                max_distance,
+            } => SoftPinConstraint2D::with_anchor(
+                &particle_id,
+                &self.anchor,
+                stiffness,
+                max_distance,
+            ),
+        };
+        self.pull.set(0.);
+        let key = physics
+            .get_verlet_object_mut(object_id)
+            .add_constraint(Box::new(DragConstraint {
+                inner: constraint,
+                pull: self.pull.clone(),
+            }));
+
+        self.target = Some((object_id, particle_id));
+        self.constraint = Some(key);
+        true
+    }
+
+    /// Move the pointer, the dragged particle follows on the next update
+    pub fn move_to(&mut self, point: &Vector2D) {
+        self.anchor.set_position(point);
+    }
+
+    /// Track the pointer and check the break force, call after every
+    /// [`VerletPhysics2D::update`]. Returns `false` if nothing is dragged any
+    /// more, because the particle or its constraint was removed or the break
+    /// force was exceeded.
+    pub fn update(&mut self, physics: &mut VerletPhysics2D) -> bool {
+        let pointer = self.anchor.get_position();
+        self.pointer_velocity = pointer - self.last_pointer;
+        self.last_pointer = pointer;
+
+        let (object_id, particle_id, constraint) = match (self.target, self.constraint) {
+            (Some((object_id, particle_id)), Some(constraint)) => {
+                (object_id, particle_id, constraint)
+            }
+            _ => return false,
+        };
+        let timestep = physics.get_timestep();
+        let object = physics.get_verlet_object(object_id);
+        if !object.has_particle(particle_id) || object.get_constraint(constraint).is_none() {
+            self.target = None;
+            self.constraint = None;
+            return false;
+        }
+
+        if let Some(break_force) = self.break_force {
+            let weight = object.get_particle(particle_id).get_weight();
+            if weight * self.pull.get() / (timestep * timestep) > break_force {
+                self.release(physics);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Let go of the dragged particle, removing its constraint and passing
+    /// the pointer velocity on to it
+    pub fn release(&mut self, physics: &mut VerletPhysics2D) {
+        let constraint = self.constraint.take();
+        let (object_id, particle_id) = match self.target.take() {
+            Some(target) => target,
+            None => return,
+        };
+        let object = physics.get_verlet_object_mut(object_id);
+        if let Some(constraint) = constraint {
+            object.remove_constraint(constraint);
+        }
+        if object.has_particle(particle_id) {
+            let p = object.get_particle_mut(particle_id);
+            let last_position = *p.get_position() - self.pointer_velocity;
+            p.set_last_position(&last_position);
+        }
+    }
+}
+
+/// Holds the dragged particle and records how far it had to be pulled
+/// during the last update
+struct DragConstraint {
+    inner: Box<dyn ParticleConstraint2D>,
+    pull: Rc<Cell<f32>>,
+}
+
+impl ParticleConstraint2D for DragConstraint {
+    fn init_internal(&mut self, particles: &[&Particle2D]) {
+        self.inner.init_internal(particles);
+    }
+
+    fn get_particle_ids(&self) -> &[ParticleKey] {
+        self.inner.get_particle_ids()
+    }
+
+    fn step(&mut self, timestep: f32) {
+        self.pull.set(0.);
+        self.inner.step(timestep);
+    }
+
+    fn apply(&mut self, particles: &mut [Particle2D]) {
+        let before = *particles[0].get_position();
+        self.inner.apply(particles);
+        let moved = (*particles[0].get_position() - before).magnitude();
+        self.pull.set(self.pull.get() + moved);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VerletObject2D;
+
+    #[test]
+    fn test_drag_and_throw() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let mut object = VerletObject2D::new("ball");
+        let particle = object.create_particle(&Vector2D::new(0., 0.));
+        let object_id = world.add_verlet_object(object);
+
+        let mut dragger = Dragger2D::new(DragMode2D::Hard, 2.);
+        assert!(!dragger.grab(&mut world, &Vector2D::new(5., 0.)));
+        assert!(dragger.grab(&mut world, &Vector2D::new(1., 0.)));
+        assert_eq!(dragger.get_target(), Some((object_id, particle)));
+        let (_, constraint) = dragger.get_constraint().unwrap();
+        assert!(world
+            .get_verlet_object(object_id)
+            .get_constraint(constraint)
+            .is_some());
+
+        for i in 1..4 {
+            dragger.move_to(&Vector2D::new(1. + i as f32, 0.));
+            world.update();
+            assert!(dragger.update(&mut world));
+        }
+        let p = world.get_verlet_object(object_id).get_particle(particle);
+        assert_eq!(*p.get_position(), Vector2D::new(4., 0.));
+
+        dragger.release(&mut world);
+        assert!(!dragger.is_dragging());
+        assert!(world
+            .get_verlet_object(object_id)
+            .get_constraint_keys()
+            .is_empty());
+        world.update();
+        let p = world.get_verlet_object(object_id).get_particle(particle);
+        assert_eq!(*p.get_position(), Vector2D::new(5., 0.));
+
+        dragger.set_break_force(Some(1.5));
+        assert!(dragger.grab(&mut world, &Vector2D::new(5., 0.)));
+        dragger.move_to(&Vector2D::new(8., 0.));
+        world.update();
+        assert!(!dragger.update(&mut world));
+        assert!(!dragger.is_dragging());
+    }
+}
@@ -0,0 +1,104 @@
+use slotmap::{new_key_type, SlotMap};
+
+use crate::objects::ObjectKey;
+use crate::{ParticleKey, Spring2D, SpringActuator2D, VerletObject2D};
+
+new_key_type! { pub struct JointKey; }
+
+/// A spring between particles that may belong to different
+/// [`VerletObject2D`]s.
+///
+/// Joints are added to [`VerletPhysics2D`](crate::VerletPhysics2D) and are
+/// relaxed together with the springs of every object, so they can tie a rope
+/// to a cloth or join two objects.
+pub struct Joint2D {
+    object_a_id: ObjectKey,
+    object_b_id: ObjectKey,
+    spring: Spring2D,
+}
+
+impl Joint2D {
+    pub fn new(
+        object_a_id: &ObjectKey,
+        particle_a_id: &ParticleKey,
+        object_b_id: &ObjectKey,
+        particle_b_id: &ParticleKey,
+        stiffness: f32,
+        rest_length: Option<f32>,
+    ) -> Self {
+        Joint2D {
+            object_a_id: *object_a_id,
+            object_b_id: *object_b_id,
+            spring: Spring2D::new(particle_a_id, particle_b_id, stiffness, rest_length),
+        }
+    }
+
+    pub(crate) fn init_internal(&mut self, objects: &SlotMap<ObjectKey, VerletObject2D>) {
+        let a = objects[self.object_a_id].get_particle(self.spring.get_particle_a_id());
+        let b = objects[self.object_b_id].get_particle(self.spring.get_particle_b_id());
+        self.spring.init_internal(a, b);
+    }
+
+    #[inline(always)]
+    pub fn get_object_a_id(&self) -> ObjectKey {
+        self.object_a_id
+    }
+
+    #[inline(always)]
+    pub fn get_object_b_id(&self) -> ObjectKey {
+        self.object_b_id
+    }
+
+    #[inline(always)]
+    pub fn get_particle_a_id(&self) -> ParticleKey {
+        self.spring.get_particle_a_id()
+    }
+
+    #[inline(always)]
+    pub fn get_particle_b_id(&self) -> ParticleKey {
+        self.spring.get_particle_b_id()
+    }
+
+    /// Whether the joint is attached to the particle
+    pub fn connects(&self, object_id: ObjectKey, particle_id: ParticleKey) -> bool {
+        (self.object_a_id == object_id && self.get_particle_a_id() == particle_id)
+            || (self.object_b_id == object_id && self.get_particle_b_id() == particle_id)
+    }
+
+    pub fn get_rest_length(&self) -> f32 {
+        self.spring.get_rest_length()
+    }
+
+    pub fn set_rest_length(&mut self, rest_length: f32) {
+        self.spring.set_rest_length(rest_length);
+    }
+
+    pub fn set_actuator(&mut self, actuator: Option<SpringActuator2D>) {
+        self.spring.set_actuator(actuator);
+    }
+
+    #[inline(always)]
+    pub(crate) fn step(&mut self, timestep: f32) {
+        self.spring.step(timestep);
+    }
+
+    pub(crate) fn update(&self, objects: &mut SlotMap<ObjectKey, VerletObject2D>) {
+        let a_id = self.spring.get_particle_a_id();
+        let b_id = self.spring.get_particle_b_id();
+
+        if self.object_a_id == self.object_b_id {
+            if let Some(o) = objects.get_mut(self.object_a_id) {
+                if let Some([a, b]) = o.get_particle_pair_mut(a_id, b_id) {
+                    self.spring.update(a, b);
+                }
+            }
+        } else if let Some([oa, ob]) =
+            objects.get_disjoint_mut([self.object_a_id, self.object_b_id])
+        {
+            if oa.has_particle(a_id) && ob.has_particle(b_id) {
+                self.spring
+                    .update(oa.get_particle_mut(a_id), ob.get_particle_mut(b_id));
+            }
+        }
+    }
+}
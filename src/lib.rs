@@ -10,6 +10,7 @@
 #![forbid(unsafe_code)]
 
 pub mod behaviors;
+pub mod colliders;
 pub mod constraints;
 pub mod objects;
 
@@ -17,14 +18,34 @@ pub mod objects;
 pub(crate) use behaviors::ParticleBehaviour2D;
 pub(crate) use constraints::ParticleConstraint2D;
 
+mod actuator;
+mod anchor;
+mod broadphase;
+mod collision;
+mod events;
+mod interaction;
+mod joint;
 mod particle2d;
+mod path2d;
 mod physics2d;
+mod query;
+mod raycast;
 mod vector2d;
 mod spring;
 
 
+pub use actuator::SpringActuator2D;
+pub use anchor::Anchor2D;
+pub use broadphase::SpatialHash2D;
+pub use events::{
+    ContactEvent2D, ContactPhase2D, ContactTarget2D, SensorEvent2D, SensorPhase2D,
+};
+pub use interaction::{DragMode2D, Dragger2D};
+pub use joint::{Joint2D, JointKey};
 pub use particle2d::{Particle2D, ParticleKey};
+pub use path2d::Path2D;
 pub use physics2d::VerletPhysics2D;
+pub use raycast::RaycastHit2D;
 pub use vector2d::Vector2D;
-pub use spring::Spring2D;
-pub use objects::VerletObject2D;
+pub use spring::{Spring2D, SpringKey};
+pub use objects::{ObjectKey, RemoveParticleError2D, RemovedParticle2D, VerletObject2D};
@@ -1,15 +1,35 @@
+mod removal;
 mod utils;
 
+use std::collections::{HashMap, HashSet};
+
+use crate::behaviors::BehaviorScope2D;
+use crate::colliders::{CollisionFilter2D, Material2D};
+use crate::constraints::ConstraintKey;
 use crate::Vector2D;
-use crate::{Particle2D, ParticleBehaviour2D, ParticleConstraint2D, ParticleKey, Spring2D};
+use crate::{
+    Particle2D, ParticleBehaviour2D, ParticleConstraint2D, ParticleKey, Spring2D, SpringKey,
+};
+
+use slotmap::{new_key_type, SecondaryMap, SlotMap};
 
-use slotmap::SlotMap;
+new_key_type! { pub struct ObjectKey; }
 
 pub struct VerletObject2D {
     tag: &'static str,
     particles: SlotMap<ParticleKey, Particle2D>,
-    springs: Vec<Spring2D>,
-    constraints: Vec<Box<dyn ParticleConstraint2D>>,
+    springs: SlotMap<SpringKey, Spring2D>,
+    spring_index: HashMap<(ParticleKey, ParticleKey), SpringKey>,
+    particle_springs: SecondaryMap<ParticleKey, Vec<SpringKey>>,
+    disabled_springs: HashSet<SpringKey>,
+    constraints: SlotMap<ConstraintKey, Box<dyn ParticleConstraint2D>>,
+    disabled_constraints: HashSet<ConstraintKey>,
+    collisions: bool,
+    self_collisions: bool,
+    edge_collisions: bool,
+    collision_filter: CollisionFilter2D,
+    ignore_connected_collisions: bool,
+    material: Material2D,
 }
 
 impl VerletObject2D {
@@ -17,8 +37,18 @@ impl VerletObject2D {
         VerletObject2D {
             tag,
             particles: SlotMap::with_key(),
-            springs: Vec::new(),
-            constraints: Vec::new(),
+            springs: SlotMap::with_key(),
+            spring_index: HashMap::new(),
+            particle_springs: SecondaryMap::new(),
+            disabled_springs: HashSet::new(),
+            constraints: SlotMap::with_key(),
+            disabled_constraints: HashSet::new(),
+            collisions: false,
+            self_collisions: false,
+            edge_collisions: false,
+            collision_filter: CollisionFilter2D::default(),
+            ignore_connected_collisions: false,
+            material: Material2D::default(),
         }
     }
 
@@ -26,16 +56,136 @@ impl VerletObject2D {
         self.tag
     }
 
+    /// Let particles of this object collide with particles of other objects
+    /// that also have collisions enabled
+    pub fn set_collisions(&mut self, enabled: bool) {
+        self.collisions = enabled;
+    }
+
+    pub fn has_collisions(&self) -> bool {
+        self.collisions
+    }
+
+    /// Let particles of this object collide with each other
+    pub fn set_self_collisions(&mut self, enabled: bool) {
+        self.self_collisions = enabled;
+    }
+
+    pub fn has_self_collisions(&self) -> bool {
+        self.self_collisions
+    }
+
+    /// Treat the springs of this object as capsules that particles and
+    /// other springs collide with, following the same self and cross-object
+    /// rules as particle collisions
+    pub fn set_edge_collisions(&mut self, enabled: bool) {
+        self.edge_collisions = enabled;
+    }
+
+    pub fn has_edge_collisions(&self) -> bool {
+        self.edge_collisions
+    }
+
+    /// Filter used for all particles of this object, unless a particle
+    /// overrides it
+    pub fn set_collision_filter(&mut self, filter: CollisionFilter2D) {
+        self.collision_filter = filter;
+    }
+
+    #[inline(always)]
+    pub fn get_collision_filter(&self) -> CollisionFilter2D {
+        self.collision_filter
+    }
+
+    /// Skip collisions between two particles of this object that are
+    /// joined by a spring
+    pub fn set_ignore_connected_collisions(&mut self, ignore: bool) {
+        self.ignore_connected_collisions = ignore;
+    }
+
+    pub fn ignores_connected_collisions(&self) -> bool {
+        self.ignore_connected_collisions
+    }
+
+    /// Material of all particles of this object when touching colliders
+    pub fn set_material(&mut self, material: Material2D) {
+        self.material = material;
+    }
+
+    #[inline(always)]
+    pub fn get_material(&self) -> &Material2D {
+        &self.material
+    }
+
     // handle particle functions
 
     pub fn create_particle(&mut self, position: &Vector2D) -> ParticleKey {
-        return self
-            .particles
-            .insert_with_key(|k| (Particle2D::new(k, position)));
+        self.particles
+            .insert_with_key(|k| Particle2D::new(k, position))
     }
 
-    pub fn remove_particle(&mut self, particle_id: ParticleKey) {
-        self.particles.remove(particle_id);
+    /// Remove a particle along with every spring and constraint attached to
+    /// it, returning what was removed
+    pub fn remove_particle(&mut self, particle_id: ParticleKey) -> Option<RemovedParticle2D> {
+        let particle = self.particles.remove(particle_id)?;
+
+        let (spring_ids, constraint_ids) = self.particle_dependents(particle_id);
+        let springs = spring_ids
+            .into_iter()
+            .filter_map(|k| self.remove_spring(k))
+            .collect();
+        let constraints = constraint_ids
+            .into_iter()
+            .filter_map(|k| self.remove_constraint(k))
+            .collect();
+        self.particle_springs.remove(particle_id);
+
+        Some(RemovedParticle2D {
+            particle,
+            springs,
+            constraints,
+            joints: Vec::new(),
+        })
+    }
+
+    /// Remove a particle only if no spring or constraint depends on it,
+    /// otherwise list what does
+    pub fn try_remove_particle(
+        &mut self,
+        particle_id: ParticleKey,
+    ) -> Result<Particle2D, RemoveParticleError2D> {
+        if !self.has_particle(particle_id) {
+            return Err(RemoveParticleError2D::NotFound);
+        }
+        let (springs, constraints) = self.particle_dependents(particle_id);
+        if !springs.is_empty() || !constraints.is_empty() {
+            return Err(RemoveParticleError2D::InUse {
+                springs,
+                constraints,
+                joints: Vec::new(),
+            });
+        }
+        Ok(self.particles.remove(particle_id).unwrap())
+    }
+
+    /// Springs and constraints that use the particle
+    pub(crate) fn particle_dependents(
+        &self,
+        particle_id: ParticleKey,
+    ) -> (Vec<SpringKey>, Vec<ConstraintKey>) {
+        let springs = self.get_particle_springs(particle_id).to_vec();
+        let constraints = self
+            .constraints
+            .iter()
+            .filter(|(_, c)| c.get_particle_ids().contains(&particle_id))
+            .map(|(k, _)| k)
+            .collect();
+        (springs, constraints)
+    }
+
+    #[inline(always)]
+    pub fn has_particle(&self, particle_id: ParticleKey) -> bool {
+        self.particles.contains_key(particle_id)
     }
 
     #[inline(always)]
@@ -43,20 +193,49 @@ impl VerletObject2D {
         &self.particles[particle_id]
     }
 
+    #[inline(always)]
+    pub fn get_particle_mut(&mut self, particle_id: ParticleKey) -> &mut Particle2D {
+        &mut self.particles[particle_id]
+    }
+
+    #[inline(always)]
+    pub(crate) fn get_particle_pair_mut(
+        &mut self,
+        particle_a_id: ParticleKey,
+        particle_b_id: ParticleKey,
+    ) -> Option<[&mut Particle2D; 2]> {
+        self.particles
+            .get_disjoint_mut([particle_a_id, particle_b_id])
+    }
+
     pub fn get_particles(&self) -> Vec<&Particle2D> {
         self.particles.values().collect()
     }
 
+    #[inline(always)]
+    pub(crate) fn particles_mut(&mut self) -> impl Iterator<Item = &mut Particle2D> {
+        self.particles.values_mut()
+    }
+
     #[inline(always)]
     pub(crate) fn update_particles(
         &mut self,
+        object_id: ObjectKey,
         drag: f32,
-        behaviors: &Vec<Box<dyn ParticleBehaviour2D>>,
+        behaviors: &[(Box<dyn ParticleBehaviour2D>, BehaviorScope2D)],
     ) {
+        let tag = self.tag;
+        let behaviors: Vec<_> = behaviors
+            .iter()
+            .filter(|(_, scope)| scope.includes_object(object_id, tag))
+            .collect();
+
         for p in self.particles.values_mut() {
-            // apply all behaviors to each particle
-            for b in behaviors.iter() {
-                b.apply(p);
+            // apply all behaviors in scope to each particle
+            for (b, scope) in behaviors.iter() {
+                if scope.includes_particle(object_id, p) {
+                    b.apply(p);
+                }
             }
 
             // update particle's position due to external forces like
@@ -71,96 +250,421 @@ impl VerletObject2D {
     /// Add spring to physics engine
     ///
     /// Does not allow to add already added spring again. So at a time only
-    /// a single connection can exist between two unique particles
-    pub fn add_spring(&mut self, mut spring: Spring2D) {
+    /// a single connection can exist between two unique particles, in either
+    /// direction, `None` is returned for a duplicate
+    pub fn add_spring(&mut self, mut spring: Spring2D) -> Option<SpringKey> {
         let a_id = spring.get_particle_a_id();
         let b_id = spring.get_particle_b_id();
-        match self.get_spring(a_id, b_id) {
-            None => {
-                spring.init_internal(&self.particles[a_id], &self.particles[b_id]);
-                self.springs.push(spring)
+        if self.get_spring_key(a_id, b_id).is_some() {
+            return None;
+        }
+        spring.init_internal(&self.particles[a_id], &self.particles[b_id]);
+
+        let spring_id = self.springs.insert(spring);
+        self.spring_index.insert(spring_pair(a_id, b_id), spring_id);
+        for p in [a_id, b_id] {
+            if let Some(entry) = self.particle_springs.entry(p) {
+                entry.or_default().push(spring_id);
             }
-            _ => (),
         }
+        Some(spring_id)
     }
 
     pub fn add_springs(&mut self, mut springs: Vec<Spring2D>) {
-        for _ in 0..springs.len() {
-            match springs.pop() {
-                Some(s) => self.add_spring(s),
-                None => (),
+        while let Some(s) = springs.pop() {
+            self.add_spring(s);
+        }
+    }
+
+    pub fn remove_spring(&mut self, spring_id: SpringKey) -> Option<Spring2D> {
+        let spring = self.springs.remove(spring_id)?;
+        self.disabled_springs.remove(&spring_id);
+        let (a_id, b_id) = (spring.get_particle_a_id(), spring.get_particle_b_id());
+        self.spring_index.remove(&spring_pair(a_id, b_id));
+        for p in [a_id, b_id] {
+            if let Some(springs) = self.particle_springs.get_mut(p) {
+                springs.retain(|k| *k != spring_id);
             }
         }
+        Some(spring)
     }
 
+    /// Spring between the two particles, in either direction
     pub fn get_spring(
         &self,
         particle_a_id: ParticleKey,
         particle_b_id: ParticleKey,
     ) -> Option<&Spring2D> {
-        for s in self.springs.iter() {
-            if particle_a_id == s.get_particle_a_id() && particle_b_id == s.get_particle_b_id() {
-                return Some(s);
-            }
+        let spring_id = self.get_spring_key(particle_a_id, particle_b_id)?;
+        self.springs.get(spring_id)
+    }
+
+    pub fn get_spring_mut(
+        &mut self,
+        particle_a_id: ParticleKey,
+        particle_b_id: ParticleKey,
+    ) -> Option<&mut Spring2D> {
+        let spring_id = self.get_spring_key(particle_a_id, particle_b_id)?;
+        self.springs.get_mut(spring_id)
+    }
+
+    /// Key of the spring between the two particles, in either direction
+    pub fn get_spring_key(
+        &self,
+        particle_a_id: ParticleKey,
+        particle_b_id: ParticleKey,
+    ) -> Option<SpringKey> {
+        self.spring_index
+            .get(&spring_pair(particle_a_id, particle_b_id))
+            .copied()
+    }
+
+    /// Keys of all springs attached to the particle
+    pub fn get_particle_springs(&self, particle_id: ParticleKey) -> &[SpringKey] {
+        self.particle_springs
+            .get(particle_id)
+            .map_or(&[], |springs| springs.as_slice())
+    }
+
+    pub fn get_spring_by_key(&self, spring_id: SpringKey) -> Option<&Spring2D> {
+        self.springs.get(spring_id)
+    }
+
+    pub fn get_spring_by_key_mut(&mut self, spring_id: SpringKey) -> Option<&mut Spring2D> {
+        self.springs.get_mut(spring_id)
+    }
+
+    pub fn get_springs(&self) -> Vec<&Spring2D> {
+        self.springs.values().collect()
+    }
+
+    /// All springs with their keys
+    pub fn get_springs_with_keys(&self) -> impl Iterator<Item = (SpringKey, &Spring2D)> {
+        self.springs.iter()
+    }
+
+    /// Disabled springs keep their place in the object but are ignored by
+    /// the simulation, collisions and queries
+    pub fn set_spring_enabled(&mut self, spring_id: SpringKey, enabled: bool) {
+        if enabled {
+            self.disabled_springs.remove(&spring_id);
+        } else if self.springs.contains_key(spring_id) {
+            self.disabled_springs.insert(spring_id);
         }
-        return None;
     }
 
-    pub fn get_springs(&self) -> &Vec<Spring2D> {
-        &self.springs
+    pub fn is_spring_enabled(&self, spring_id: SpringKey) -> bool {
+        self.springs.contains_key(spring_id) && !self.disabled_springs.contains(&spring_id)
+    }
+
+    /// Springs that are not disabled, with their keys
+    pub(crate) fn enabled_springs(&self) -> impl Iterator<Item = (SpringKey, &Spring2D)> {
+        self.springs
+            .iter()
+            .filter(move |(k, _)| !self.disabled_springs.contains(k))
     }
 
     #[inline(always)]
-    pub(crate) fn update_springs(&mut self, num_iterations: u32) {
-        for _ in 0..num_iterations {
-            for s in self.springs.iter() {
-                let maybe_ab = self
-                    .particles
-                    .get_disjoint_mut([s.get_particle_a_id(), s.get_particle_b_id()]);
-                match maybe_ab {
-                    Some([a, b]) => {
-                        s.update(a, b);
-                    }
-                    None => (),
-                }
+    pub(crate) fn step_springs(&mut self, timestep: f32) {
+        for (k, s) in self.springs.iter_mut() {
+            if !self.disabled_springs.contains(&k) {
+                s.step(timestep);
+            }
+        }
+    }
+
+    /// Relax all springs once
+    #[inline(always)]
+    pub(crate) fn update_springs(&mut self) {
+        for (k, s) in self.springs.iter() {
+            if self.disabled_springs.contains(&k) {
+                continue;
+            }
+            let maybe_ab = self
+                .particles
+                .get_disjoint_mut([s.get_particle_a_id(), s.get_particle_b_id()]);
+            if let Some([a, b]) = maybe_ab {
+                s.update(a, b);
             }
         }
     }
 
     // handle constraints
-    pub fn add_constraint(&mut self, mut c: Box<dyn ParticleConstraint2D>) {
-        let particle_id = c.get_particle_id();
-        c.init_internal(&self.particles[particle_id]);
 
-        self.constraints.push(c);
+    /// Add constraint to the object
+    ///
+    /// Panics if any of the particles of the constraint is not part of
+    /// this object, or if the constraint lists the same particle twice
+    pub fn add_constraint(&mut self, mut c: Box<dyn ParticleConstraint2D>) -> ConstraintKey {
+        let ids = c.get_particle_ids();
+        for (i, k) in ids.iter().enumerate() {
+            if ids[..i].contains(k) {
+                panic!("constraint lists particle {:?} more than once", k);
+            }
+        }
+        let particles: Vec<&Particle2D> = ids.iter().map(|k| &self.particles[*k]).collect();
+        c.init_internal(&particles);
+
+        self.constraints.insert(c)
+    }
+
+    pub fn remove_constraint(
+        &mut self,
+        constraint_id: ConstraintKey,
+    ) -> Option<Box<dyn ParticleConstraint2D>> {
+        self.disabled_constraints.remove(&constraint_id);
+        self.constraints.remove(constraint_id)
+    }
+
+    pub fn get_constraint(
+        &self,
+        constraint_id: ConstraintKey,
+    ) -> Option<&dyn ParticleConstraint2D> {
+        self.constraints.get(constraint_id).map(|c| c.as_ref())
+    }
+
+    pub fn get_constraint_mut(
+        &mut self,
+        constraint_id: ConstraintKey,
+    ) -> Option<&mut dyn ParticleConstraint2D> {
+        let c = self.constraints.get_mut(constraint_id)?;
+        Some(c.as_mut())
+    }
+
+    /// The constraint as its concrete type, `None` if it does not exist or
+    /// is of another type
+    pub fn get_constraint_as<T: ParticleConstraint2D + 'static>(
+        &self,
+        constraint_id: ConstraintKey,
+    ) -> Option<&T> {
+        self.get_constraint(constraint_id)?.as_any().downcast_ref()
+    }
+
+    /// The constraint as its concrete type to change its settings, e.g.
+    /// `get_constraint_as_mut::<SoftPinConstraint2D>(key)`
+    pub fn get_constraint_as_mut<T: ParticleConstraint2D + 'static>(
+        &mut self,
+        constraint_id: ConstraintKey,
+    ) -> Option<&mut T> {
+        self.get_constraint_mut(constraint_id)?
+            .as_any_mut()
+            .downcast_mut()
+    }
+
+    pub fn get_constraint_keys(&self) -> Vec<ConstraintKey> {
+        self.constraints.keys().collect()
+    }
+
+    /// Disabled constraints keep their place in the object but are neither
+    /// stepped nor applied
+    pub fn set_constraint_enabled(&mut self, constraint_id: ConstraintKey, enabled: bool) {
+        if enabled {
+            self.disabled_constraints.remove(&constraint_id);
+        } else if self.constraints.contains_key(constraint_id) {
+            self.disabled_constraints.insert(constraint_id);
+        }
+    }
+
+    pub fn is_constraint_enabled(&self, constraint_id: ConstraintKey) -> bool {
+        self.constraints.contains_key(constraint_id)
+            && !self.disabled_constraints.contains(&constraint_id)
     }
 
     #[inline(always)]
-    pub(crate) fn apply_constraints(&mut self) {
-        for c in self.constraints.iter_mut() {
-            let particle_id = c.get_particle_id();
-            c.apply(&mut self.particles[particle_id]);
+    pub(crate) fn step_constraints(&mut self, timestep: f32) {
+        for (k, c) in self.constraints.iter_mut() {
+            if !self.disabled_constraints.contains(&k) {
+                c.step(timestep);
+            }
         }
     }
 
-    /// update verlet object a single step
-    pub(crate) fn update(
-        &mut self,
-        num_iterations: u32,
-        drag: f32,
-        behaviors: &Vec<Box<dyn ParticleBehaviour2D>>,
-    ) {
-        self.update_particles(drag, behaviors);
-        self.apply_constraints();
-        self.update_springs(num_iterations);
-        self.apply_constraints();
+    #[inline(always)]
+    pub(crate) fn apply_constraints(&mut self) {
+        let mut scratch = Vec::new();
+        for (k, c) in self.constraints.iter_mut() {
+            if !self.disabled_constraints.contains(&k) {
+                apply_constraint(&mut self.particles, c.as_mut(), &mut scratch);
+            }
+        }
     }
 
     pub fn clear(&mut self) {
         self.particles.clear();
         self.springs.clear();
+        self.spring_index.clear();
+        self.particle_springs.clear();
+        self.disabled_springs.clear();
         self.constraints.clear();
+        self.disabled_constraints.clear();
     }
 }
 
+/// Key of the spring index, the same for both directions
+#[inline]
+fn spring_pair(a: ParticleKey, b: ParticleKey) -> (ParticleKey, ParticleKey) {
+    (a.min(b), a.max(b))
+}
+
+/// Runs `c` over copies of its particles and writes the results back, which
+/// gives the constraint disjoint mutable access to any number of particles.
+/// Constraints whose particles no longer exist are skipped.
+fn apply_constraint(
+    particles: &mut SlotMap<ParticleKey, Particle2D>,
+    c: &mut dyn ParticleConstraint2D,
+    scratch: &mut Vec<Particle2D>,
+) {
+    scratch.clear();
+    for k in c.get_particle_ids() {
+        match particles.get(*k) {
+            Some(p) => scratch.push(p.clone()),
+            None => return,
+        }
+    }
+
+    c.apply(scratch);
+
+    for p in scratch.drain(..) {
+        let id = p.get_id();
+        particles[id] = p;
+    }
+}
+
+pub use removal::{RemoveParticleError2D, RemovedParticle2D};
 pub use utils::{create_line_from_endpoints, create_line_from_points, create_sheet};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MidpointConstraint {
+        ids: [ParticleKey; 2],
+    }
+
+    impl ParticleConstraint2D for MidpointConstraint {
+        fn init_internal(&mut self, _particles: &[&Particle2D]) {}
+
+        fn get_particle_ids(&self) -> &[ParticleKey] {
+            &self.ids
+        }
+
+        fn apply(&mut self, particles: &mut [Particle2D]) {
+            let mid = (particles[0].position + particles[1].position) / 2.;
+            particles[0].set_position(&mid);
+            particles[1].set_position(&mid);
+        }
+    }
+
+    #[test]
+    fn test_multi_particle_constraint() {
+        let mut obj = VerletObject2D::new("test");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let b = obj.create_particle(&Vector2D::new(4., 2.));
+        obj.add_constraint(Box::new(MidpointConstraint { ids: [a, b] }));
+        obj.apply_constraints();
+
+        assert_eq!(obj.get_particle(a).get_position().x, 2.);
+        assert_eq!(obj.get_particle(b).get_position().y, 1.);
+    }
+
+    #[test]
+    fn test_remove_particle_cascades() {
+        use crate::constraints::PinConstraint2D;
+
+        let mut obj = VerletObject2D::new("test");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let b = obj.create_particle(&Vector2D::new(1., 0.));
+        let c = obj.create_particle(&Vector2D::new(2., 0.));
+        obj.add_spring(Spring2D::new(&a, &b, 1., None));
+        obj.add_spring(Spring2D::new(&b, &c, 1., None));
+        obj.add_constraint(PinConstraint2D::new(&a));
+
+        assert_eq!(
+            obj.try_remove_particle(a).err(),
+            Some(RemoveParticleError2D::InUse {
+                springs: vec![obj.get_spring_key(a, b).unwrap()],
+                constraints: obj.get_constraint_keys(),
+                joints: Vec::new(),
+            })
+        );
+
+        let removed = obj.remove_particle(a).unwrap();
+        assert_eq!(removed.particle.get_id(), a);
+        assert_eq!(removed.springs.len(), 1);
+        assert_eq!(removed.constraints.len(), 1);
+        assert_eq!(obj.get_springs().len(), 1);
+        assert!(obj.remove_particle(a).is_none());
+        obj.apply_constraints();
+
+        obj.remove_particle(c);
+        assert!(obj.try_remove_particle(b).is_ok());
+        assert_eq!(
+            obj.try_remove_particle(b).err(),
+            Some(RemoveParticleError2D::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_spring_and_constraint_keys() {
+        use crate::constraints::PinConstraint2D;
+
+        let mut obj = VerletObject2D::new("test");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let b = obj.create_particle(&Vector2D::new(2., 0.));
+        let spring = obj.add_spring(Spring2D::new(&a, &b, 1., Some(1.))).unwrap();
+        assert!(obj.add_spring(Spring2D::new(&a, &b, 1., None)).is_none());
+        assert!(obj.add_spring(Spring2D::new(&b, &a, 1., None)).is_none());
+        assert_eq!(obj.get_spring_key(a, b), Some(spring));
+        assert_eq!(obj.get_spring_key(b, a), Some(spring));
+        assert_eq!(obj.get_particle_springs(b), &[spring]);
+
+        let pin = obj.add_constraint(PinConstraint2D::new(&a));
+        obj.get_particle_mut(a).set_position(&Vector2D::new(0., 1.));
+        obj.set_constraint_enabled(pin, false);
+        obj.apply_constraints();
+        assert_eq!(obj.get_particle(a).get_position().y, 1.);
+        obj.set_constraint_enabled(pin, true);
+        obj.apply_constraints();
+        assert_eq!(obj.get_particle(a).get_position().y, 0.);
+
+        obj.set_spring_enabled(spring, false);
+        assert!(!obj.is_spring_enabled(spring));
+        obj.update_springs();
+        assert_eq!(obj.get_particle(b).get_position().x, 2.);
+
+        assert!(obj.remove_spring(spring).is_some());
+        assert!(obj.remove_constraint(pin).is_some());
+        assert!(obj.get_spring(a, b).is_none());
+        assert!(obj.get_particle_springs(a).is_empty());
+        assert!(obj.get_constraint(pin).is_none());
+        assert!(!obj.is_constraint_enabled(pin));
+        assert!(!obj.is_spring_enabled(spring));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn test_constraint_with_duplicate_particles_panics() {
+        use crate::constraints::AngleConstraint2D;
+
+        let mut obj = VerletObject2D::new("test");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let c = obj.create_particle(&Vector2D::new(1., 0.));
+        obj.add_constraint(AngleConstraint2D::new(&a, &a, &c, 1.));
+    }
+
+    #[test]
+    fn test_get_constraint_as_concrete_type() {
+        use crate::constraints::{PinConstraint2D, SoftPinConstraint2D};
+
+        let mut obj = VerletObject2D::new("test");
+        let a = obj.create_particle(&Vector2D::new(0., 0.));
+        let pin = obj.add_constraint(SoftPinConstraint2D::new(&a, &Vector2D::zero(), 0.5, None));
+
+        assert!(obj.get_constraint_as::<PinConstraint2D>(pin).is_none());
+        obj.get_constraint_as_mut::<SoftPinConstraint2D>(pin)
+            .unwrap()
+            .set_max_distance(Some(2.));
+        let soft_pin = obj.get_constraint_as::<SoftPinConstraint2D>(pin).unwrap();
+        assert_eq!(soft_pin.get_max_distance(), Some(2.));
+    }
+}
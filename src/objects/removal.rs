@@ -0,0 +1,52 @@
+use std::fmt;
+
+use crate::constraints::ConstraintKey;
+use crate::{Joint2D, JointKey, Particle2D, ParticleConstraint2D, Spring2D, SpringKey};
+
+/// Everything removed together with a particle
+pub struct RemovedParticle2D {
+    pub particle: Particle2D,
+    /// Springs that were attached to the particle
+    pub springs: Vec<Spring2D>,
+    /// Constraints that acted on the particle
+    pub constraints: Vec<Box<dyn ParticleConstraint2D>>,
+    /// Joints that were attached to the particle, only filled when removing
+    /// through [`VerletPhysics2D`](crate::VerletPhysics2D)
+    pub joints: Vec<Joint2D>,
+}
+
+/// Why a particle could not be removed
+#[derive(Debug, PartialEq)]
+pub enum RemoveParticleError2D {
+    NotFound,
+    /// Other parts of the simulation still depend on the particle
+    InUse {
+        /// Springs attached to the particle
+        springs: Vec<SpringKey>,
+        /// Constraints acting on the particle
+        constraints: Vec<ConstraintKey>,
+        /// Joints attached to the particle
+        joints: Vec<JointKey>,
+    },
+}
+
+impl fmt::Display for RemoveParticleError2D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoveParticleError2D::NotFound => write!(f, "particle not found"),
+            RemoveParticleError2D::InUse {
+                springs,
+                constraints,
+                joints,
+            } => write!(
+                f,
+                "particle is used by {} springs, {} constraints and {} joints",
+                springs.len(),
+                constraints.len(),
+                joints.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RemoveParticleError2D {}
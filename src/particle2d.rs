@@ -1,9 +1,11 @@
 use slotmap::new_key_type;
 
+use crate::colliders::CollisionFilter2D;
 use crate::vector2d::Vector2D;
 
 new_key_type! { pub struct ParticleKey; }
 
+#[derive(Clone)]
 pub struct Particle2D {
     id: ParticleKey,
     pub(crate) position: Vector2D,
@@ -11,6 +13,9 @@ pub struct Particle2D {
     force: Vector2D,
     weight: f32,
     pub(crate) inv_weight: f32,
+    radius: f32,
+    filter: Option<CollisionFilter2D>,
+    charge: f32,
 }
 
 impl Particle2D {
@@ -23,6 +28,9 @@ impl Particle2D {
             force: Vector2D::zero(),
             weight: 1f32,
             inv_weight: 1f32,
+            radius: 0.,
+            filter: None,
+            charge: 0.,
         }
     }
 
@@ -41,6 +49,23 @@ impl Particle2D {
         &self.position
     }
 
+    #[inline]
+    pub fn set_position(&mut self, position: &Vector2D) {
+        self.position.set_v(position);
+    }
+
+    /// Position at the previous step, the difference to the current position
+    /// is the particle's velocity
+    #[inline]
+    pub fn get_last_position(&self) -> &Vector2D {
+        &self.last_position
+    }
+
+    #[inline]
+    pub fn set_last_position(&mut self, last_position: &Vector2D) {
+        self.last_position.set_v(last_position);
+    }
+
     #[inline]
     pub fn get_id(&self) -> ParticleKey {
         self.id
@@ -50,11 +75,80 @@ impl Particle2D {
         self.weight
     }
 
+    /// Radius used for collisions, a radius of zero uses the engine's
+    /// default particle radius
+    #[inline]
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(0.);
+    }
+
+    #[inline]
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Radius for collisions, falling back to `default_radius`
+    #[inline]
+    pub(crate) fn collision_radius(&self, default_radius: f32) -> f32 {
+        if self.radius > 0. {
+            self.radius
+        } else {
+            default_radius
+        }
+    }
+
+    /// Collision filter overriding the one of the particle's object, `None`
+    /// uses the object's filter
+    #[inline]
+    pub fn set_collision_filter(&mut self, filter: Option<CollisionFilter2D>) {
+        self.filter = filter;
+    }
+
+    #[inline]
+    pub fn get_collision_filter(&self) -> Option<CollisionFilter2D> {
+        self.filter
+    }
+
+    /// Collision filter, falling back to `object_filter`
+    #[inline]
+    pub(crate) fn effective_filter(&self, object_filter: CollisionFilter2D) -> CollisionFilter2D {
+        self.filter.unwrap_or(object_filter)
+    }
+
+    /// Charge used by
+    /// [`ChargeInteraction2D`](crate::behaviors::ChargeInteraction2D),
+    /// particles with charges of the same sign repel each other
+    #[inline]
+    pub fn set_charge(&mut self, charge: f32) {
+        self.charge = charge;
+    }
+
+    #[inline]
+    pub fn get_charge(&self) -> f32 {
+        self.charge
+    }
+
+    /// Inverse of the weight, zero for particles with zero weight
+    #[inline]
+    pub fn get_inv_weight(&self) -> f32 {
+        self.inv_weight
+    }
+
+    /// Force for the next step, which changes the velocity by
+    /// `force * weight`, so a constant force moves heavy particles faster
     #[inline]
     pub(crate) fn add_force(&mut self, force: &Vector2D) {
         self.force += *force;
     }
 
+    /// Physical force for the next step, which changes the velocity by
+    /// `force / weight`, so equal and opposite forces on two particles keep
+    /// their total momentum
+    #[inline]
+    pub(crate) fn add_physical_force(&mut self, force: &Vector2D) {
+        self.force += *force * (self.inv_weight * self.inv_weight);
+    }
+
     #[inline]
     pub(crate) fn update(&mut self, drag: f32) {
         // apply drag
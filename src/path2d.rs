@@ -0,0 +1,90 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::Vector2D;
+
+/// A piecewise linear path through timed keyframes.
+pub struct Path2D {
+    keyframes: Vec<(f32, Vector2D)>,
+    looping: bool,
+}
+
+impl Path2D {
+    /// Creates a path from `(time, position)` keyframes.
+    ///
+    /// Keyframes are sorted by time. When `looping` is set the path restarts
+    /// from the first keyframe once the last one is reached, otherwise it
+    /// stays at the last keyframe.
+    pub fn new(mut keyframes: Vec<(f32, Vector2D)>, looping: bool) -> Path2D {
+        keyframes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Path2D { keyframes, looping }
+    }
+
+    pub fn get_duration(&self) -> f32 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => last.0 - first.0,
+            _ => 0.,
+        }
+    }
+
+    /// Position on the path at `time`
+    pub fn sample(&self, time: f32) -> Vector2D {
+        sample_keyframes(&self.keyframes, self.looping, time).unwrap_or_else(Vector2D::zero)
+    }
+}
+
+/// Linearly interpolates sorted `(time, value)` keyframes at `time`
+pub(crate) fn sample_keyframes<T>(keyframes: &[(f32, T)], looping: bool, time: f32) -> Option<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    let (first, last) = match (keyframes.first(), keyframes.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return None,
+    };
+
+    let duration = last.0 - first.0;
+    let mut t = time;
+    if looping && duration > 0. {
+        t = first.0 + (t - first.0).rem_euclid(duration);
+    }
+    if t <= first.0 {
+        return Some(first.1);
+    }
+    if t >= last.0 {
+        return Some(last.1);
+    }
+
+    for w in keyframes.windows(2) {
+        let (t0, v0) = w[0];
+        let (t1, v1) = w[1];
+        if t <= t1 {
+            let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1. };
+            return Some(v0 + (v1 - v0) * s);
+        }
+    }
+    Some(last.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path2d_sample() {
+        let path = Path2D::new(
+            vec![(0., Vector2D::new(0., 0.)), (2., Vector2D::new(10., 0.))],
+            false,
+        );
+        assert_eq!(path.sample(1.).x, 5.);
+        assert_eq!(path.sample(5.).x, 10.);
+    }
+
+    #[test]
+    fn test_path2d_looping() {
+        let path = Path2D::new(
+            vec![(0., Vector2D::new(0., 0.)), (2., Vector2D::new(10., 0.))],
+            true,
+        );
+        assert_eq!(path.sample(3.).x, 5.);
+    }
+}
@@ -1,12 +1,41 @@
-use crate::behaviors::ConstantForceBehavior2D;
-use crate::{ParticleBehaviour2D, VerletObject2D};
+use slotmap::SlotMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::behaviors::{
+    BehaviorScope2D, ConstantForceBehavior2D, InteractionParticle2D, ParticleInteraction2D,
+};
+use crate::colliders::{Collider2D, ColliderKey, Sensor2D, SensorKey};
+use crate::collision::{ColliderContacts, ParticleCollisions};
+use crate::events::{
+    Contact, ContactEvent2D, ContactPhase2D, ContactTarget2D, SensorEvent2D, SensorPhase2D,
+};
+use crate::query::{nearest_particle, nearest_spring, particles_where};
+use crate::raycast::{cast, RaycastHit2D};
+use crate::{
+    Joint2D, JointKey, ObjectKey, Particle2D, ParticleBehaviour2D, ParticleKey,
+    RemoveParticleError2D, RemovedParticle2D, SpatialHash2D, SpringKey, Vector2D, VerletObject2D,
+};
 
 pub struct VerletPhysics2D {
     timestep: f32,
     num_iterations: u32,
     drag: f32,
-    behaviors: Vec<Box<dyn ParticleBehaviour2D>>,
-    objects: Vec<VerletObject2D>,
+    behaviors: Vec<(Box<dyn ParticleBehaviour2D>, BehaviorScope2D)>,
+    interactions: Vec<(Box<dyn ParticleInteraction2D>, BehaviorScope2D)>,
+    objects: SlotMap<ObjectKey, VerletObject2D>,
+    joints: SlotMap<JointKey, Joint2D>,
+    colliders: SlotMap<ColliderKey, Collider2D>,
+    particle_radius: f32,
+    particle_collisions: ParticleCollisions,
+    broadphase: SpatialHash2D<(ObjectKey, ParticleKey)>,
+    collider_contacts: ColliderContacts,
+    continuous_collisions: bool,
+    contacts: Vec<Contact>,
+    active_contacts: HashSet<(ObjectKey, ParticleKey, ContactTarget2D)>,
+    contact_events: Vec<ContactEvent2D>,
+    sensors: SlotMap<SensorKey, Sensor2D>,
+    sensor_overlaps: HashSet<(SensorKey, ObjectKey, Option<ParticleKey>)>,
+    sensor_events: Vec<SensorEvent2D>,
 }
 
 impl VerletPhysics2D {
@@ -24,7 +53,10 @@ impl VerletPhysics2D {
     ) -> VerletPhysics2D {
         let behaviors = match gravity {
             Some(g_value) => {
-                vec![ConstantForceBehavior2D::new(0., g_value)]
+                vec![(
+                    ConstantForceBehavior2D::new(0., g_value),
+                    BehaviorScope2D::All,
+                )]
             }
             None => Vec::new(),
         };
@@ -33,41 +65,916 @@ impl VerletPhysics2D {
             num_iterations,
             drag,
             behaviors,
-            objects: Vec::new(),
+            interactions: Vec::new(),
+            objects: SlotMap::with_key(),
+            joints: SlotMap::with_key(),
+            colliders: SlotMap::with_key(),
+            particle_radius: 0.,
+            particle_collisions: ParticleCollisions::default(),
+            broadphase: SpatialHash2D::new(16.),
+            collider_contacts: ColliderContacts::default(),
+            continuous_collisions: false,
+            contacts: Vec::new(),
+            active_contacts: HashSet::new(),
+            contact_events: Vec::new(),
+            sensors: SlotMap::with_key(),
+            sensor_overlaps: HashSet::new(),
+            sensor_events: Vec::new(),
         }
     }
 
+    pub fn get_timestep(&self) -> f32 {
+        self.timestep
+    }
+
     pub fn set_drag(&mut self, drag: f32) {
         self.drag = drag;
     }
 
+    /// Default radius for particles that have no radius of their own, used
+    /// when colliding with colliders and other particles
+    pub fn set_particle_radius(&mut self, radius: f32) {
+        self.particle_radius = radius;
+    }
+
+    pub fn get_particle_radius(&self) -> f32 {
+        self.particle_radius
+    }
+
+    /// Cell size of the broadphase grid, best set to about the diameter of
+    /// the particles. Smaller cells still find every pair, but each particle
+    /// then spans many cells.
+    ///
+    /// Panics if `cell_size` is not a positive finite number
+    pub fn set_broadphase_cell_size(&mut self, cell_size: f32) {
+        self.broadphase.set_cell_size(cell_size);
+    }
+
+    /// Grid of all particles, rebuilt at every update after particles are
+    /// moved by behaviors and before springs and collisions are solved
+    pub fn get_broadphase(&self) -> &SpatialHash2D<(ObjectKey, ParticleKey)> {
+        &self.broadphase
+    }
+
+    /// Sweep particles from their last position to their new one against
+    /// colliders and spring edges, so fast particles cannot pass through
+    /// thin shapes
+    pub fn set_continuous_collisions(&mut self, enabled: bool) {
+        self.continuous_collisions = enabled;
+    }
+
+    pub fn has_continuous_collisions(&self) -> bool {
+        self.continuous_collisions
+    }
+
     // handle behavior functions
 
-    pub fn add_behavior(&mut self, mut b: Box<dyn ParticleBehaviour2D>) {
+    /// Add a behaviour acting on every particle
+    pub fn add_behavior(&mut self, b: Box<dyn ParticleBehaviour2D>) {
+        self.add_scoped_behavior(b, BehaviorScope2D::All);
+    }
+
+    /// Add a behaviour acting only on the particles in `scope`
+    pub fn add_scoped_behavior(
+        &mut self,
+        mut b: Box<dyn ParticleBehaviour2D>,
+        scope: BehaviorScope2D,
+    ) {
         b.configure(self.timestep);
-        self.behaviors.push(b);
+        self.behaviors.push((b, scope));
+    }
+
+    /// Add a force acting between every pair of particles
+    pub fn add_interaction(&mut self, i: Box<dyn ParticleInteraction2D>) {
+        self.add_scoped_interaction(i, BehaviorScope2D::All);
+    }
+
+    /// Add a force acting between the particles in `scope`
+    pub fn add_scoped_interaction(
+        &mut self,
+        mut i: Box<dyn ParticleInteraction2D>,
+        scope: BehaviorScope2D,
+    ) {
+        i.configure(self.timestep);
+        self.interactions.push((i, scope));
+    }
+
+    /// Add the forces of all interactions to the particles, each one seeing
+    /// the particles as they were at the start of the update
+    fn apply_interactions(&mut self) {
+        let mut snapshot = Vec::new();
+        let mut forces = Vec::new();
+        for (interaction, scope) in self.interactions.iter() {
+            snapshot.clear();
+            for (object_id, o) in self.objects.iter() {
+                if !scope.includes_object(object_id, o.get_tag()) {
+                    continue;
+                }
+                for p in o.get_particles() {
+                    if scope.includes_particle(object_id, p) {
+                        snapshot.push(InteractionParticle2D {
+                            object: object_id,
+                            particle: p.get_id(),
+                            position: *p.get_position(),
+                            weight: p.get_weight(),
+                            charge: p.get_charge(),
+                        });
+                    }
+                }
+            }
+
+            forces.clear();
+            forces.resize(snapshot.len(), Vector2D::zero());
+            interaction.apply(&snapshot, &mut forces);
+            for (p, force) in snapshot.iter().zip(forces.iter()) {
+                self.objects[p.object]
+                    .get_particle_mut(p.particle)
+                    .add_physical_force(force);
+            }
+        }
+    }
+
+    pub fn add_verlet_object(&mut self, object: VerletObject2D) -> ObjectKey {
+        self.objects.insert(object)
+    }
+
+    #[inline(always)]
+    pub fn get_verlet_object(&self, object_id: ObjectKey) -> &VerletObject2D {
+        &self.objects[object_id]
+    }
+
+    #[inline(always)]
+    pub fn get_verlet_object_mut(&mut self, object_id: ObjectKey) -> &mut VerletObject2D {
+        &mut self.objects[object_id]
+    }
+
+    pub fn get_verlet_objects(&self) -> Vec<&VerletObject2D> {
+        self.objects.values().collect()
+    }
+
+    // handle joint functions
+
+    /// Add a joint between particles of the same or different objects
+    ///
+    /// Panics if either object or particle does not exist
+    pub fn add_joint(&mut self, mut joint: Joint2D) -> JointKey {
+        joint.init_internal(&self.objects);
+        self.joints.insert(joint)
+    }
+
+    pub fn remove_joint(&mut self, joint_id: JointKey) -> Option<Joint2D> {
+        self.joints.remove(joint_id)
+    }
+
+    pub fn get_joint(&self, joint_id: JointKey) -> Option<&Joint2D> {
+        self.joints.get(joint_id)
+    }
+
+    pub fn get_joint_mut(&mut self, joint_id: JointKey) -> Option<&mut Joint2D> {
+        self.joints.get_mut(joint_id)
+    }
+
+    /// Remove a particle from its object along with every spring,
+    /// constraint and joint attached to it, returning what was removed
+    pub fn remove_particle(
+        &mut self,
+        object_id: ObjectKey,
+        particle_id: ParticleKey,
+    ) -> Option<RemovedParticle2D> {
+        let mut removed = self
+            .objects
+            .get_mut(object_id)?
+            .remove_particle(particle_id)?;
+        removed.joints = self
+            .joint_dependents(object_id, particle_id)
+            .into_iter()
+            .filter_map(|k| self.joints.remove(k))
+            .collect();
+        Some(removed)
+    }
+
+    /// Remove a particle only if no spring, constraint or joint depends on
+    /// it, otherwise list what does
+    pub fn try_remove_particle(
+        &mut self,
+        object_id: ObjectKey,
+        particle_id: ParticleKey,
+    ) -> Result<Particle2D, RemoveParticleError2D> {
+        let joints = self.joint_dependents(object_id, particle_id);
+        let object = self
+            .objects
+            .get_mut(object_id)
+            .ok_or(RemoveParticleError2D::NotFound)?;
+        if !object.has_particle(particle_id) {
+            return Err(RemoveParticleError2D::NotFound);
+        }
+        let (springs, constraints) = object.particle_dependents(particle_id);
+        if !springs.is_empty() || !constraints.is_empty() || !joints.is_empty() {
+            return Err(RemoveParticleError2D::InUse {
+                springs,
+                constraints,
+                joints,
+            });
+        }
+        object.try_remove_particle(particle_id)
+    }
+
+    pub fn get_joints(&self) -> Vec<&Joint2D> {
+        self.joints.values().collect()
+    }
+
+    fn joint_dependents(&self, object_id: ObjectKey, particle_id: ParticleKey) -> Vec<JointKey> {
+        self.joints
+            .iter()
+            .filter(|(_, j)| j.connects(object_id, particle_id))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    // handle collider functions
+
+    pub fn add_collider(&mut self, collider: Collider2D) -> ColliderKey {
+        self.colliders.insert(collider)
+    }
+
+    pub fn remove_collider(&mut self, collider_id: ColliderKey) -> Option<Collider2D> {
+        self.colliders.remove(collider_id)
+    }
+
+    pub fn get_collider(&self, collider_id: ColliderKey) -> Option<&Collider2D> {
+        self.colliders.get(collider_id)
+    }
+
+    pub fn get_collider_mut(&mut self, collider_id: ColliderKey) -> Option<&mut Collider2D> {
+        self.colliders.get_mut(collider_id)
+    }
+
+    pub fn get_colliders(&self) -> Vec<&Collider2D> {
+        self.colliders.values().collect()
+    }
+
+    pub fn add_sensor(&mut self, sensor: Sensor2D) -> SensorKey {
+        self.sensors.insert(sensor)
+    }
+
+    pub fn remove_sensor(&mut self, sensor_id: SensorKey) -> Option<Sensor2D> {
+        self.sensors.remove(sensor_id)
+    }
+
+    pub fn get_sensor(&self, sensor_id: SensorKey) -> Option<&Sensor2D> {
+        self.sensors.get(sensor_id)
+    }
+
+    pub fn get_sensor_mut(&mut self, sensor_id: SensorKey) -> Option<&mut Sensor2D> {
+        self.sensors.get_mut(sensor_id)
+    }
+
+    pub fn get_sensors(&self) -> Vec<&Sensor2D> {
+        self.sensors.values().collect()
+    }
+
+    /// Particles and objects that entered, stayed in or left a sensor during
+    /// the last step
+    pub fn get_sensor_events(&self) -> &[SensorEvent2D] {
+        &self.sensor_events
+    }
+
+    /// Find what overlaps every sensor and compare it with the overlaps of
+    /// the previous step
+    fn update_sensor_events(&mut self) {
+        let previous = std::mem::take(&mut self.sensor_events);
+        let mut overlaps = HashSet::new();
+        for (sensor_id, s) in self.sensors.iter() {
+            for (object_id, o) in self.objects.iter() {
+                let object_filter = o.get_collision_filter();
+                let mut inside = false;
+                for p in o.get_particles() {
+                    let filter = p.effective_filter(object_filter);
+                    if !filter.collides_with(&s.get_collision_filter()) {
+                        continue;
+                    }
+                    let radius = p.collision_radius(self.particle_radius);
+                    if s.get_shape().penetration(&p.position, radius).is_none() {
+                        continue;
+                    }
+                    inside = true;
+                    let id = (sensor_id, object_id, Some(p.get_id()));
+                    self.sensor_events.push(SensorEvent2D {
+                        phase: if self.sensor_overlaps.contains(&id) {
+                            SensorPhase2D::Stay
+                        } else {
+                            SensorPhase2D::Enter
+                        },
+                        sensor: sensor_id,
+                        object: object_id,
+                        tag: o.get_tag(),
+                        particle: Some(p.get_id()),
+                    });
+                    overlaps.insert(id);
+                }
+
+                if inside {
+                    let id = (sensor_id, object_id, None);
+                    self.sensor_events.push(SensorEvent2D {
+                        phase: if self.sensor_overlaps.contains(&id) {
+                            SensorPhase2D::Stay
+                        } else {
+                            SensorPhase2D::Enter
+                        },
+                        sensor: sensor_id,
+                        object: object_id,
+                        tag: o.get_tag(),
+                        particle: None,
+                    });
+                    overlaps.insert(id);
+                }
+            }
+        }
+
+        // everything that overlapped last step and does not any more has left
+        for event in previous {
+            let id = (event.sensor, event.object, event.particle);
+            if event.phase != SensorPhase2D::Exit && !overlaps.contains(&id) {
+                self.sensor_events.push(SensorEvent2D {
+                    phase: SensorPhase2D::Exit,
+                    ..event
+                });
+            }
+        }
+        self.sensor_overlaps = overlaps;
+    }
+
+    // handle query functions, these scan every particle or spring instead
+    // of using the broadphase, which is stale once constraints move
+    // particles at the end of an update
+
+    /// Particle closest to `point`, if one is within `max_distance`
+    ///
+    /// Checks every particle, O(n)
+    pub fn nearest_particle(
+        &self,
+        point: &Vector2D,
+        max_distance: f32,
+    ) -> Option<(ObjectKey, ParticleKey)> {
+        nearest_particle(&self.objects, point, max_distance)
+    }
+
+    /// Particles whose position is inside the circle
+    ///
+    /// Checks every particle, O(n)
+    pub fn particles_in_circle(
+        &self,
+        center: &Vector2D,
+        radius: f32,
+    ) -> Vec<(ObjectKey, ParticleKey)> {
+        particles_where(&self.objects, |p| {
+            (*p - *center).magnitude_sq() <= radius * radius
+        })
+    }
+
+    /// Particles whose position is inside the axis aligned rectangle
+    ///
+    /// Checks every particle, O(n)
+    pub fn particles_in_rect(
+        &self,
+        min: &Vector2D,
+        max: &Vector2D,
+    ) -> Vec<(ObjectKey, ParticleKey)> {
+        particles_where(&self.objects, |p| {
+            p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+        })
+    }
+
+    /// Enabled spring closest to `point`, if one is within `max_distance`,
+    /// given by its object and key
+    ///
+    /// Checks every spring, O(n)
+    pub fn nearest_spring(
+        &self,
+        point: &Vector2D,
+        max_distance: f32,
+    ) -> Option<(ObjectKey, SpringKey)> {
+        nearest_spring(&self.objects, point, max_distance)
+    }
+
+    /// Closest particle, spring or collider hit by a ray from `origin` along
+    /// `direction`, up to `max_distance` away
+    pub fn raycast(
+        &self,
+        origin: &Vector2D,
+        direction: &Vector2D,
+        max_distance: f32,
+    ) -> Option<RaycastHit2D> {
+        self.raycast_all(origin, direction, max_distance)
+            .into_iter()
+            .next()
+    }
+
+    /// Everything hit by a ray from `origin` along `direction`, up to
+    /// `max_distance` away, closest first
+    pub fn raycast_all(
+        &self,
+        origin: &Vector2D,
+        direction: &Vector2D,
+        max_distance: f32,
+    ) -> Vec<RaycastHit2D> {
+        let to = *origin + direction.normalize() * max_distance;
+        self.segment_cast_all(origin, &to)
+    }
+
+    /// Closest particle, spring or collider hit on the segment from `from`
+    /// to `to`
+    pub fn segment_cast(&self, from: &Vector2D, to: &Vector2D) -> Option<RaycastHit2D> {
+        self.circle_cast(from, to, 0.)
+    }
+
+    /// Everything hit on the segment from `from` to `to`, closest first
+    pub fn segment_cast_all(&self, from: &Vector2D, to: &Vector2D) -> Vec<RaycastHit2D> {
+        self.circle_cast_all(from, to, 0.)
+    }
+
+    /// Closest particle, spring or collider hit by a circle of `radius`
+    /// moving from `from` to `to`
+    pub fn circle_cast(&self, from: &Vector2D, to: &Vector2D, radius: f32) -> Option<RaycastHit2D> {
+        self.circle_cast_all(from, to, radius).into_iter().next()
+    }
+
+    /// Everything hit by a circle of `radius` moving from `from` to `to`,
+    /// closest first. Things already overlapping the circle at `from` are
+    /// not reported.
+    pub fn circle_cast_all(
+        &self,
+        from: &Vector2D,
+        to: &Vector2D,
+        radius: f32,
+    ) -> Vec<RaycastHit2D> {
+        cast(
+            &self.objects,
+            &self.colliders,
+            self.particle_radius,
+            from,
+            to,
+            radius,
+        )
+    }
+
+    /// Push all particles out of the static colliders
+    fn resolve_collisions(&mut self) {
+        if self.colliders.is_empty() {
+            return;
+        }
+
+        for (object_id, o) in self.objects.iter_mut() {
+            let object_filter = o.get_collision_filter();
+            for p in o.particles_mut() {
+                if p.inv_weight == 0. {
+                    continue;
+                }
+                let radius = p.collision_radius(self.particle_radius);
+                let filter = p.effective_filter(object_filter);
+                for (collider_id, c) in self.colliders.iter() {
+                    if !filter.collides_with(&c.get_collision_filter()) {
+                        continue;
+                    }
+                    if let Some((normal, depth)) = c.get_shape().penetration(&p.position, radius) {
+                        p.position += normal * depth;
+                        self.collider_contacts.record(
+                            object_id,
+                            p.get_id(),
+                            collider_id,
+                            normal,
+                            depth,
+                            depth * p.get_weight(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stop particles at the first collider they hit on the way from their
+    /// last position, sliding along it for the rest of the step
+    fn sweep_collisions(&mut self) {
+        if self.colliders.is_empty() {
+            return;
+        }
+
+        for (object_id, o) in self.objects.iter_mut() {
+            let object_filter = o.get_collision_filter();
+            for p in o.particles_mut() {
+                if p.inv_weight == 0. || p.position == p.last_position {
+                    continue;
+                }
+                let radius = p.collision_radius(self.particle_radius);
+                let filter = p.effective_filter(object_filter);
+
+                let mut first = None;
+                for (collider_id, c) in self.colliders.iter() {
+                    if !filter.collides_with(&c.get_collision_filter()) {
+                        continue;
+                    }
+                    if let Some((t, normal)) =
+                        c.get_shape().sweep(&p.last_position, &p.position, radius)
+                    {
+                        if first.is_none_or(|(first_t, _, _)| t < first_t) {
+                            first = Some((t, normal, collider_id));
+                        }
+                    }
+                }
+
+                if let Some((t, normal, collider_id)) = first {
+                    let motion = p.position - p.last_position;
+                    let remaining = motion * (1. - t);
+                    let into = remaining.dot(normal).min(0.);
+                    p.position = p.last_position + motion * t + remaining - normal * into;
+                    self.collider_contacts.record(
+                        object_id,
+                        p.get_id(),
+                        collider_id,
+                        normal,
+                        -into,
+                        -into * p.get_weight(),
+                    );
+                }
+            }
+        }
     }
 
-    pub fn add_verlet_object(&mut self, object: VerletObject2D) {
-        self.objects.push(object);
+    fn rebuild_broadphase(&mut self) {
+        self.broadphase.clear();
+        for (object_id, o) in self.objects.iter() {
+            for p in o.get_particles() {
+                self.broadphase.insert(
+                    (object_id, p.get_id()),
+                    p.get_position(),
+                    p.collision_radius(self.particle_radius),
+                );
+            }
+        }
     }
 
-    pub fn get_verlet_objects(&self) -> &Vec<VerletObject2D>{
-        &self.objects
+    /// Contacts that began, persisted or ended during the last step
+    pub fn get_contact_events(&self) -> &[ContactEvent2D] {
+        &self.contact_events
+    }
+
+    /// Turn the contacts found this step into events, comparing them with
+    /// the contacts of the previous step
+    fn update_contact_events(&mut self) {
+        self.contacts.clear();
+        self.collider_contacts.collect_contacts(&mut self.contacts);
+        self.particle_collisions
+            .collect_contacts(&mut self.contacts);
+
+        let previous = std::mem::take(&mut self.contact_events);
+        let mut active = HashMap::with_capacity(self.contacts.len());
+        for c in self.contacts.iter() {
+            let id = (c.object, c.particle, c.other);
+            // a particle can touch the same spring both directly and through
+            // its own springs, report that as one contact
+            if let Some(&i) = active.get(&id) {
+                let event: &mut ContactEvent2D = &mut self.contact_events[i];
+                if c.depth > event.depth {
+                    event.normal = c.normal;
+                    event.depth = c.depth;
+                }
+                event.impulse += c.impulse;
+                continue;
+            }
+            let phase = if self.active_contacts.contains(&id) {
+                ContactPhase2D::Persist
+            } else {
+                ContactPhase2D::Begin
+            };
+            self.contact_events.push(ContactEvent2D {
+                phase,
+                object: c.object,
+                tag: self.objects.get(c.object).map_or("", |o| o.get_tag()),
+                particle: c.particle,
+                other: c.other,
+                normal: c.normal,
+                depth: c.depth,
+                impulse: c.impulse,
+            });
+            active.insert(id, self.contact_events.len() - 1);
+        }
+
+        // every contact of the last step that was not found again has ended
+        for event in previous {
+            let id = (event.object, event.particle, event.other);
+            if event.phase != ContactPhase2D::End && !active.contains_key(&id) {
+                self.contact_events.push(ContactEvent2D {
+                    phase: ContactPhase2D::End,
+                    depth: 0.,
+                    impulse: 0.,
+                    ..event
+                });
+            }
+        }
+        self.active_contacts = active.into_keys().collect();
     }
 
     /// run the engine for a single step
     pub fn update(&mut self) {
-        for o in self.objects.iter_mut() {
-            o.update(self.num_iterations, self.drag, &self.behaviors);
+        self.apply_interactions();
+
+        for j in self.joints.values_mut() {
+            j.step(self.timestep);
+        }
+
+        for (object_id, o) in self.objects.iter_mut() {
+            o.step_springs(self.timestep);
+            o.step_constraints(self.timestep);
+            o.update_particles(object_id, self.drag, &self.behaviors);
+            o.apply_constraints();
+        }
+
+        self.collider_contacts.clear();
+        if self.continuous_collisions {
+            self.sweep_collisions();
+        }
+        self.rebuild_broadphase();
+        self.particle_collisions.prepare(
+            &self.objects,
+            &self.broadphase,
+            self.particle_radius,
+            self.continuous_collisions,
+        );
+        if self.continuous_collisions {
+            self.particle_collisions.sweep_edges(&mut self.objects);
+        }
+
+        // relax object springs and joints together, so joints are solved
+        // with the same stiffness as the springs they connect
+        for _ in 0..self.num_iterations {
+            for o in self.objects.values_mut() {
+                o.update_springs();
+            }
+            for j in self.joints.values() {
+                j.update(&mut self.objects);
+            }
+            self.particle_collisions.resolve(&mut self.objects);
+            self.resolve_collisions();
+        }
+
+        self.collider_contacts
+            .apply_materials(&mut self.objects, &self.colliders);
+        self.update_contact_events();
+        self.update_sensor_events();
+
+        for o in self.objects.values_mut() {
+            o.apply_constraints();
         }
     }
 
     pub fn clear(&mut self) {
         self.behaviors.clear();
+        self.interactions.clear();
 
-        for o in self.objects.iter_mut() {
+        self.joints.clear();
+        self.colliders.clear();
+        self.broadphase.clear();
+        self.active_contacts.clear();
+        self.contact_events.clear();
+        self.sensors.clear();
+        self.sensor_overlaps.clear();
+        self.sensor_events.clear();
+
+        for o in self.objects.values_mut() {
             o.clear();
         }
     }
 }
+
+impl Default for VerletPhysics2D {
+    fn default() -> Self {
+        VerletPhysics2D::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colliders::Shape2D;
+
+    #[test]
+    fn test_contact_events_begin_persist_end() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(1.0);
+        world.add_behavior(ConstantForceBehavior2D::new(0., -0.1));
+        let mut object = VerletObject2D::new("ball");
+        let particle = object.create_particle(&Vector2D::new(0., 0.5));
+        let object_id = world.add_verlet_object(object);
+        let collider = world.add_collider(Collider2D::new(Shape2D::segment(
+            &Vector2D::new(-10., 0.),
+            &Vector2D::new(10., 0.),
+        )));
+
+        world.update();
+        let events = world.get_contact_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, ContactPhase2D::Begin);
+        assert_eq!(events[0].object, object_id);
+        assert_eq!(events[0].tag, "ball");
+        assert_eq!(events[0].particle, particle);
+        assert!(events[0].other == ContactTarget2D::Collider(collider));
+        assert!(events[0].depth > 0.);
+
+        world.update();
+        assert_eq!(world.get_contact_events()[0].phase, ContactPhase2D::Persist);
+
+        world.remove_collider(collider);
+        world.update();
+        let events = world.get_contact_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, ContactPhase2D::End);
+    }
+
+    #[test]
+    fn test_sensor_events_enter_stay_exit() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let mut object = VerletObject2D::new("ball");
+        let particle = object.create_particle(&Vector2D::new(-2., 0.));
+        object
+            .get_particle_mut(particle)
+            .set_last_position(&Vector2D::new(-3., 0.));
+        world.add_verlet_object(object);
+        let sensor = world.add_sensor(Sensor2D::new(Shape2D::aabb(
+            &Vector2D::new(-1.5, -1.),
+            &Vector2D::new(1.5, 1.),
+        )));
+
+        let mut phases = Vec::new();
+        for _ in 0..6 {
+            world.update();
+            for e in world.get_sensor_events() {
+                assert!(e.sensor == sensor);
+                if e.particle == Some(particle) {
+                    phases.push(e.phase);
+                }
+            }
+        }
+        assert_eq!(
+            phases,
+            vec![
+                SensorPhase2D::Enter,
+                SensorPhase2D::Stay,
+                SensorPhase2D::Stay,
+                SensorPhase2D::Exit
+            ]
+        );
+        // the particle is not pushed out
+        let p = world.get_verlet_objects()[0].get_particles()[0].get_position();
+        assert_eq!(*p, Vector2D::new(4., 0.));
+    }
+
+    #[test]
+    fn test_scoped_behaviors() {
+        let mut world = VerletPhysics2D::new_with(1.0, 1, 0.0, None);
+        let mut flag = VerletObject2D::new("flag");
+        let cloth = flag.create_particle(&Vector2D::new(0., 0.));
+        let flag_id = world.add_verlet_object(flag);
+        let mut chain = VerletObject2D::new("chain");
+        let link = chain.create_particle(&Vector2D::new(0., 0.));
+        let metal = chain.create_particle(&Vector2D::new(10., 0.));
+        let chain_id = world.add_verlet_object(chain);
+
+        world.add_scoped_behavior(
+            ConstantForceBehavior2D::new(1., 0.),
+            BehaviorScope2D::Tag("flag"),
+        );
+        world.add_scoped_behavior(
+            ConstantForceBehavior2D::new(0., 1.),
+            BehaviorScope2D::filter(|_, p| p.get_position().x > 5.),
+        );
+        world.update();
+
+        let position = |o: ObjectKey, p: ParticleKey| {
+            *world.get_verlet_object(o).get_particle(p).get_position()
+        };
+        assert_eq!(position(flag_id, cloth), Vector2D::new(1., 0.));
+        assert_eq!(position(chain_id, link), Vector2D::new(0., 0.));
+        assert_eq!(position(chain_id, metal), Vector2D::new(10., 1.));
+    }
+
+    #[test]
+    fn test_joint_between_objects() {
+        let mut world = VerletPhysics2D::new_with(1.0, 50, 0.0, None);
+        let mut rope = VerletObject2D::new("rope");
+        let a = rope.create_particle(&Vector2D::new(0., 0.));
+        let mut box_ = VerletObject2D::new("box");
+        let b = box_.create_particle(&Vector2D::new(4., 0.));
+        let rope_id = world.add_verlet_object(rope);
+        let box_id = world.add_verlet_object(box_);
+
+        let joint = world.add_joint(Joint2D::new(&rope_id, &a, &box_id, &b, 0.5, Some(2.)));
+        world.update();
+        let pa = *world
+            .get_verlet_object(rope_id)
+            .get_particle(a)
+            .get_position();
+        let pb = *world
+            .get_verlet_object(box_id)
+            .get_particle(b)
+            .get_position();
+        assert!(((pb - pa).magnitude() - 2.).abs() < 1e-3);
+
+        world.get_joint_mut(joint).unwrap().set_rest_length(3.);
+        assert_eq!(world.get_joint(joint).unwrap().get_rest_length(), 3.);
+        assert!(world.remove_joint(joint).is_some());
+        assert!(world.get_joint(joint).is_none());
+        assert!(world.get_joints().is_empty());
+    }
+
+    #[test]
+    fn test_touching_edges_report_contacts() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(0.5);
+        let mut rope = |from: Vector2D, to: Vector2D| {
+            let mut rope = VerletObject2D::new("rope");
+            let a = rope.create_particle(&from);
+            let b = rope.create_particle(&to);
+            rope.add_spring(crate::Spring2D::new(&a, &b, 0.5, None));
+            rope.set_collisions(true);
+            rope.set_edge_collisions(true);
+            (world.add_verlet_object(rope), a, b)
+        };
+        let (first, a1, b1) = rope(Vector2D::new(-2., 0.), Vector2D::new(2., 0.));
+        let (second, a2, b2) = rope(Vector2D::new(0.5, 0.8), Vector2D::new(1.5, 3.));
+
+        world.update();
+        let events = world.get_contact_events();
+        assert_eq!(events.len(), 2);
+        let on_first = events.iter().find(|e| e.object == first).unwrap();
+        let on_second = events.iter().find(|e| e.object == second).unwrap();
+        assert_eq!(on_first.phase, ContactPhase2D::Begin);
+        // each edge reports the contact on its particle nearest to it
+        assert_eq!(on_first.particle, b1);
+        assert_eq!(on_second.particle, a2);
+        assert!(on_first.other == ContactTarget2D::Spring(second, a2, b2));
+        assert!(on_second.other == ContactTarget2D::Spring(first, a1, b1));
+        assert!(on_first.normal.y < 0. && on_second.normal.y > 0.);
+        assert!((on_second.depth - 0.2).abs() < 1e-4);
+        assert!(on_first.impulse > 0. && on_second.impulse > 0.);
+    }
+
+    #[test]
+    fn test_impulse_is_weight_times_correction() {
+        let mut world = VerletPhysics2D::new_with(1.0, 1, 0.0, None);
+        world.set_particle_radius(1.0);
+        let mut object = VerletObject2D::new("ball");
+        let particle = object.create_particle(&Vector2D::new(0., 0.5));
+        object.get_particle_mut(particle).set_weight(2.);
+        world.add_verlet_object(object);
+        world.add_collider(Collider2D::new(Shape2D::segment(
+            &Vector2D::new(-10., 0.),
+            &Vector2D::new(10., 0.),
+        )));
+
+        world.update();
+        let event = world.get_contact_events()[0];
+        assert!((event.depth - 0.5).abs() < 1e-6);
+        assert!((event.impulse - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_try_remove_particle_reports_joints() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        let mut rope = VerletObject2D::new("rope");
+        let a = rope.create_particle(&Vector2D::new(0., 0.));
+        let mut anchor = VerletObject2D::new("anchor");
+        let b = anchor.create_particle(&Vector2D::new(1., 0.));
+        let rope_id = world.add_verlet_object(rope);
+        let anchor_id = world.add_verlet_object(anchor);
+        let joint = world.add_joint(Joint2D::new(&rope_id, &a, &anchor_id, &b, 0.5, None));
+
+        assert_eq!(
+            world.try_remove_particle(rope_id, a).err(),
+            Some(RemoveParticleError2D::InUse {
+                springs: vec![],
+                constraints: vec![],
+                joints: vec![joint],
+            })
+        );
+        let removed = world.remove_particle(rope_id, a).unwrap();
+        assert_eq!(removed.joints.len(), 1);
+        assert!(world.get_joint(joint).is_none());
+        assert!(world.try_remove_particle(anchor_id, b).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "cell size must be positive")]
+    fn test_broadphase_cell_size_rejects_negative() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(1.0);
+        world.set_broadphase_cell_size(-5.);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell size must be positive")]
+    fn test_broadphase_cell_size_rejects_nan() {
+        let mut world = VerletPhysics2D::new_with(1.0, 10, 0.0, None);
+        world.set_particle_radius(1.0);
+        world.set_broadphase_cell_size(f32::NAN);
+    }
+}
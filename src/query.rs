@@ -0,0 +1,105 @@
+use slotmap::SlotMap;
+
+use crate::colliders::closest_point_on_segment;
+use crate::{ObjectKey, ParticleKey, SpringKey, Vector2D, VerletObject2D};
+
+/// Particle closest to `point`, if one is within `max_distance`
+pub(crate) fn nearest_particle(
+    objects: &SlotMap<ObjectKey, VerletObject2D>,
+    point: &Vector2D,
+    max_distance: f32,
+) -> Option<(ObjectKey, ParticleKey)> {
+    let mut best = None;
+    let mut best_dist_sq = max_distance * max_distance;
+    for (object_id, o) in objects.iter() {
+        for p in o.get_particles() {
+            let dist_sq = (*p.get_position() - *point).magnitude_sq();
+            if dist_sq <= best_dist_sq {
+                best_dist_sq = dist_sq;
+                best = Some((object_id, p.get_id()));
+            }
+        }
+    }
+    best
+}
+
+/// Particles whose position passes `inside`
+pub(crate) fn particles_where(
+    objects: &SlotMap<ObjectKey, VerletObject2D>,
+    inside: impl Fn(&Vector2D) -> bool,
+) -> Vec<(ObjectKey, ParticleKey)> {
+    let mut found = Vec::new();
+    for (object_id, o) in objects.iter() {
+        for p in o.get_particles() {
+            if inside(p.get_position()) {
+                found.push((object_id, p.get_id()));
+            }
+        }
+    }
+    found
+}
+
+/// Enabled spring closest to `point`, if one is within `max_distance`
+pub(crate) fn nearest_spring(
+    objects: &SlotMap<ObjectKey, VerletObject2D>,
+    point: &Vector2D,
+    max_distance: f32,
+) -> Option<(ObjectKey, SpringKey)> {
+    let mut best = None;
+    let mut best_dist_sq = max_distance * max_distance;
+    for (object_id, o) in objects.iter() {
+        for (key, s) in o.enabled_springs() {
+            let (a, b) = (s.get_particle_a_id(), s.get_particle_b_id());
+            if !o.has_particle(a) || !o.has_particle(b) {
+                continue;
+            }
+            let closest = closest_point_on_segment(
+                point,
+                o.get_particle(a).get_position(),
+                o.get_particle(b).get_position(),
+            );
+            let dist_sq = (closest - *point).magnitude_sq();
+            if dist_sq <= best_dist_sq {
+                best_dist_sq = dist_sq;
+                best = Some((object_id, key));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spring2D;
+
+    #[test]
+    fn test_picks_nearest_particle_and_spring() {
+        let mut objects = SlotMap::with_key();
+        let mut o = VerletObject2D::new("rope");
+        let a = o.create_particle(&Vector2D::new(0., 0.));
+        let b = o.create_particle(&Vector2D::new(10., 0.));
+        let c = o.create_particle(&Vector2D::new(10., 10.));
+        let ab = o.add_spring(Spring2D::new(&a, &b, 1., None)).unwrap();
+        let bc = o.add_spring(Spring2D::new(&b, &c, 1., None)).unwrap();
+        let id = objects.insert(o);
+
+        let point = Vector2D::new(8., 1.);
+        assert_eq!(nearest_particle(&objects, &point, 5.), Some((id, b)));
+        assert_eq!(nearest_particle(&objects, &point, 1.), None);
+        assert_eq!(
+            nearest_spring(&objects, &Vector2D::new(4., 1.), 2.),
+            Some((id, ab))
+        );
+        assert_eq!(
+            nearest_spring(&objects, &Vector2D::new(9., 6.), 2.),
+            Some((id, bc))
+        );
+        // disabled springs cannot be picked
+        objects[id].set_spring_enabled(bc, false);
+        assert_eq!(nearest_spring(&objects, &Vector2D::new(9., 6.), 2.), None);
+
+        let inside = particles_where(&objects, |p| p.x > 5.);
+        assert_eq!(inside, vec![(id, b), (id, c)]);
+    }
+}
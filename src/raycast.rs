@@ -0,0 +1,106 @@
+use slotmap::SlotMap;
+
+use crate::colliders::{Collider2D, ColliderKey, Shape2D};
+use crate::{ContactTarget2D, ObjectKey, Vector2D, VerletObject2D};
+
+/// Where a ray or shape cast hit a particle, spring or collider
+#[derive(Clone, Copy, Debug)]
+pub struct RaycastHit2D {
+    pub target: ContactTarget2D,
+    /// Point on the surface that was hit
+    pub point: Vector2D,
+    /// Surface normal at `point`, facing the cast
+    pub normal: Vector2D,
+    /// Distance travelled along the cast before the hit
+    pub distance: f32,
+}
+
+/// Sweep a circle of `radius` from `from` to `to` against every particle,
+/// spring and collider, returning the hits ordered by distance. A `radius`
+/// of zero casts a ray.
+pub(crate) fn cast(
+    objects: &SlotMap<ObjectKey, VerletObject2D>,
+    colliders: &SlotMap<ColliderKey, Collider2D>,
+    default_radius: f32,
+    from: &Vector2D,
+    to: &Vector2D,
+    radius: f32,
+) -> Vec<RaycastHit2D> {
+    let length = (*to - *from).magnitude();
+    let hit = |target, (t, normal): (f32, Vector2D)| RaycastHit2D {
+        target,
+        point: *from + (*to - *from) * t - normal * radius,
+        normal,
+        distance: length * t,
+    };
+
+    let mut hits = Vec::new();
+    for (object_id, o) in objects.iter() {
+        for p in o.get_particles() {
+            let shape = Shape2D::circle(p.get_position(), p.collision_radius(default_radius));
+            if let Some(h) = shape.sweep(from, to, radius) {
+                hits.push(hit(ContactTarget2D::Particle(object_id, p.get_id()), h));
+            }
+        }
+
+        for (_, s) in o.enabled_springs() {
+            let (a_id, b_id) = (s.get_particle_a_id(), s.get_particle_b_id());
+            if !o.has_particle(a_id) || !o.has_particle(b_id) {
+                continue;
+            }
+            let (a, b) = (o.get_particle(a_id), o.get_particle(b_id));
+            let edge_radius =
+                (a.collision_radius(default_radius) + b.collision_radius(default_radius)) / 2.;
+            let shape = Shape2D::segment(a.get_position(), b.get_position());
+            if let Some(h) = shape.sweep(from, to, radius + edge_radius) {
+                hits.push(hit(ContactTarget2D::Spring(object_id, a_id, b_id), h));
+            }
+        }
+    }
+
+    for (collider_id, c) in colliders.iter() {
+        if let Some(h) = c.get_shape().sweep(from, to, radius) {
+            hits.push(hit(ContactTarget2D::Collider(collider_id), h));
+        }
+    }
+
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ray_hits_spring_before_collider() {
+        let mut objects = SlotMap::with_key();
+        let mut rope = VerletObject2D::new("rope");
+        let a = rope.create_particle(&Vector2D::new(-5., 5.));
+        let b = rope.create_particle(&Vector2D::new(5., 5.));
+        rope.add_spring(crate::Spring2D::new(&a, &b, 1., None));
+        let rope_id = objects.insert(rope);
+
+        let mut colliders = SlotMap::with_key();
+        let wall = colliders.insert(Collider2D::new(Shape2D::aabb(
+            &Vector2D::new(-5., 10.),
+            &Vector2D::new(5., 12.),
+        )));
+
+        let hits = cast(
+            &objects,
+            &colliders,
+            0.,
+            &Vector2D::new(0., 0.),
+            &Vector2D::new(0., 20.),
+            0.,
+        );
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].target == ContactTarget2D::Spring(rope_id, a, b));
+        assert_eq!(hits[0].point, Vector2D::new(0., 5.));
+        assert_eq!(hits[0].normal, Vector2D::new(0., -1.));
+        assert_eq!(hits[0].distance, 5.);
+        assert!(hits[1].target == ContactTarget2D::Collider(wall));
+        assert_eq!(hits[1].distance, 10.);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{Particle2D, ParticleKey};
+use slotmap::new_key_type;
+
+use crate::{Particle2D, ParticleKey, SpringActuator2D};
+
+new_key_type! { pub struct SpringKey; }
 
 pub struct Spring2D {
     particle_a_id: ParticleKey,
@@ -6,6 +10,9 @@ pub struct Spring2D {
     rest_length: f32,
     rest_length_sq: f32,
     stiffness: f32,
+    actuator: Option<SpringActuator2D>,
+    base_rest_length: f32,
+    time: f32,
 }
 
 impl Spring2D {
@@ -15,24 +22,45 @@ impl Spring2D {
         stiffness: f32,
         rest_length: Option<f32>,
     ) -> Self {
-        let rs = match rest_length {
-            Some(n) => n,
-            None => -1.,
-        };
+        let rs = rest_length.unwrap_or(-1.);
         Spring2D {
             particle_a_id: *particle_a_id,
             particle_b_id: *particle_b_id,
             rest_length: rs,
             rest_length_sq: rs * rs,
             stiffness,
+            actuator: None,
+            base_rest_length: rs,
+            time: 0.,
         }
     }
 
+    /// Drive the rest length of this spring with `actuator`
+    pub fn with_actuator(mut self, actuator: SpringActuator2D) -> Self {
+        self.actuator = Some(actuator);
+        self
+    }
+
+    pub fn set_actuator(&mut self, actuator: Option<SpringActuator2D>) {
+        self.actuator = actuator;
+        self.time = 0.;
+    }
+
     pub(crate) fn init_internal(&mut self, a: &Particle2D, b: &Particle2D) {
         if self.rest_length < 0. {
             let rs = (*a.get_position() - *b.get_position()).magnitude();
             self.set_rest_length(rs);
         }
+        self.base_rest_length = self.rest_length;
+    }
+
+    /// Advance the actuator, if any, by `timestep`
+    pub(crate) fn step(&mut self, timestep: f32) {
+        if let Some(actuator) = self.actuator.as_mut() {
+            self.time += timestep;
+            let rs = actuator.rest_length(self.base_rest_length, self.time);
+            self.set_rest_length(rs);
+        }
     }
 
     pub fn set_rest_length(&mut self, rest_length: f32) {
@@ -55,11 +83,15 @@ impl Spring2D {
     }
 
     pub(crate) fn update(&self, a: &mut Particle2D, b: &mut Particle2D) {
+        let w = a.inv_weight + b.inv_weight;
+        if w == 0. {
+            // both ends are fixed
+            return;
+        }
         let delta = b.position - a.position;
         let dist = delta.magnitude_sq();
 
-        let force_mag =
-            (dist - self.rest_length_sq) / (dist * (a.inv_weight + b.inv_weight)) * self.stiffness;
+        let force_mag = (dist - self.rest_length_sq) / (dist * w) * self.stiffness;
 
         a.position += delta * force_mag * a.inv_weight;
         b.position -= delta * force_mag * b.inv_weight;
@@ -5,7 +5,7 @@ use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
 ///
 /// This type is marked as `#[repr(C)]`.
 #[repr(C)]
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Debug)]
 pub struct Vector2D {
     /// The x component of the vector.
     pub x: f32,
@@ -58,6 +58,36 @@ impl Vector2D {
         self.x * v.x + self.y * v.y
     }
 
+    /// z component of the 3d cross product of the two vectors
+    #[inline]
+    pub fn cross(&self, v: Vector2D) -> f32 {
+        self.x * v.y - self.y * v.x
+    }
+
+    /// The vector rotated counter-clockwise by `angle` radians
+    #[inline]
+    pub fn rotate(&self, angle: f32) -> Vector2D {
+        let (sin, cos) = angle.sin_cos();
+        Vector2D::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Unit vector in the same direction, or zero for a zero vector
+    #[inline]
+    pub fn normalize(&self) -> Vector2D {
+        let m = self.magnitude();
+        if m > 0. {
+            *self / m
+        } else {
+            Vector2D::zero()
+        }
+    }
+
+    /// The vector rotated by 90 degrees counter-clockwise
+    #[inline]
+    pub fn perpendicular(&self) -> Vector2D {
+        Vector2D::new(-self.y, self.x)
+    }
+
     #[inline]
     pub fn zero() -> Self {
         Vector2D::new(0.0, 0.0)